@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "aoc.conf";
+
+// how to reach the advent of code website, or any server with the same endpoints
+// the config file holds "key = value" lines, the environment variables win over it:
+// session (AOC_SESSION), base_url (AOC_BASE_URL) and year (AOC_YEAR)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Config {
+    pub(crate) base_url: String,
+    pub(crate) session: Option<String>,
+    pub(crate) year: u16,
+}
+
+impl Config {
+    // the defaults, without looking at the config file or the environment
+    pub(crate) fn new() -> Config {
+        Config {
+            base_url: "https://adventofcode.com".to_string(),
+            session: None,
+            year: 2023,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "session" => self.session = Some(value.to_string()),
+            "base_url" => self.base_url = value.trim_end_matches('/').to_string(),
+            "year" => self.year = value.parse().map_err(|_| format!("{:?} is not a year", value))?,
+            _ => return Err(format!("Unknown config key {:?}", key)),
+        }
+        Ok(())
+    }
+
+    fn parse(&mut self, contents: &str) -> Result<(), String> {
+        for line in contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (key, value) = line.split_once('=').ok_or(format!("{:?} is not a key = value line", line))?;
+            self.set(key.trim(), value.trim())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn load() -> Result<Config, String> {
+        let mut config = Config::new();
+        if let Ok(contents) = std::fs::read_to_string(CONFIG_FILE) {
+            config.parse(&contents).map_err(|error| format!("{}: {}", CONFIG_FILE, error))?;
+        }
+        for (key, variable) in [("session", "AOC_SESSION"), ("base_url", "AOC_BASE_URL"), ("year", "AOC_YEAR")] {
+            if let Ok(value) = std::env::var(variable) {
+                config.set(key, &value).map_err(|error| format!("{}: {}", variable, error))?;
+            }
+        }
+        Ok(config)
+    }
+
+    fn session(&self) -> Result<&str, String> {
+        self.session
+            .as_deref()
+            .ok_or(format!("No session token, set AOC_SESSION or add a session line to {}", CONFIG_FILE))
+    }
+
+    fn agent(&self) -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .user_agent("advent_of_code_2023 solver (github.com/beregolas/AdventOfCode23)")
+            .build()
+    }
+
+    fn get(&self, path: &str) -> Result<String, String> {
+        let url = format!("{}/{}", self.base_url, path);
+        let request = self.agent().get(&url).set("Cookie", &format!("session={}", self.session()?));
+        Config::read_response(&url, request.call())
+    }
+
+    pub(crate) fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let url = format!("{}/{}", self.base_url, path);
+        let request = self.agent().post(&url).set("Cookie", &format!("session={}", self.session()?));
+        Config::read_response(&url, request.send_form(form))
+    }
+
+    fn read_response(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
+        match response {
+            Ok(response) => response.into_string().map_err(|error| format!("Could not read {}: {}", url, error)),
+            Err(ureq::Error::Status(status, response)) => Err(format!(
+                "{} answered {}: {}",
+                url,
+                status,
+                response.into_string().unwrap_or_default().trim()
+            )),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+}
+
+// downloads the input of a day into {dir}/{day}.txt, unless any input for the day is already there
+// returns the path and whether it was downloaded
+pub(crate) fn fetch_into(config: &Config, dir: &Path, day: u8) -> Result<(PathBuf, bool), String> {
+    let target = dir.join(format!("{}.txt", day));
+    // an empty file is only a placeholder, for example from new
+    let fetched = |path: &PathBuf| std::fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.len() > 0);
+    if let Some(existing) = [target.clone(), dir.join(format!("{}-1.txt", day))].into_iter().find(fetched) {
+        return Ok((existing, false));
+    }
+    let contents = config.get(&format!("{}/day/{}/input", config.year, day))?;
+    std::fs::create_dir_all(dir).map_err(|error| format!("Could not create {}: {}", dir.display(), error))?;
+    // write next to the target first, so an interrupted download never looks like a cached input
+    let partial = dir.join(format!("{}.txt.part", day));
+    std::fs::write(&partial, contents)
+        .and_then(|_| std::fs::rename(&partial, &target))
+        .map_err(|error| format!("Could not write {}: {}", target.display(), error))?;
+    Ok((target, true))
+}
+
+pub(crate) fn fetch(day: u8) -> Result<(PathBuf, bool), String> {
+    fetch_into(&Config::load()?, &crate::input::input_dir(), day)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use crate::input::tests::TempDir;
+
+    // answers exactly one request with the given body and hands back the request, head and body
+    pub(crate) fn mock_server(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_config() {
+        let mut config = Config::new();
+        config.parse("# comment\nsession = abc\nbase_url = http://localhost:8000/\n\nyear=2022").unwrap();
+        assert_eq!(config, Config {
+            base_url: "http://localhost:8000".to_string(),
+            session: Some("abc".to_string()),
+            year: 2022,
+        });
+        assert!(config.parse("colour = red").is_err());
+        assert!(config.parse("session").is_err());
+    }
+
+    #[test]
+    fn test_fetch_into() {
+        let (url, server) = mock_server("1 2 3\n");
+        let dir = TempDir::new("fetch");
+        let mut config = Config::new();
+        config.set("base_url", &url).unwrap();
+        config.set("session", "abc").unwrap();
+        assert_eq!(fetch_into(&config, &dir, 9), Ok((dir.join("9.txt"), true)));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/9/input "));
+        assert!(request.contains("session=abc"));
+        assert_eq!(std::fs::read_to_string(dir.join("9.txt")).unwrap(), "1 2 3\n");
+        // the server is gone, so this only works from the cache
+        assert_eq!(fetch_into(&config, &dir, 9), Ok((dir.join("9.txt"), false)));
+    }
+}
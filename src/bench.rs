@@ -0,0 +1,240 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::run::Record;
+
+const HISTORY_FILE: &str = "timings.txt";
+// how many runs the report shows side by side
+const REPORT_RUNS: usize = 5;
+// faster solvers are dominated by noise, so they never count as a regression
+const NOISE_FLOOR_MS: f64 = 1.0;
+
+// one solver in one run, the duration is None if it failed, status says why
+#[derive(Debug, Clone, PartialEq)]
+struct Timing {
+    timestamp: u64,
+    commit: String,
+    day: u8,
+    part: u8,
+    duration_ms: Option<f64>,
+    status: String,
+}
+
+impl Timing {
+    fn from_record(record: &Record, timestamp: u64, commit: &str) -> Timing {
+        let (duration_ms, status) = match &record.error {
+            None => (Some(record.duration.as_secs_f64() * 1000.0), "ok".to_string()),
+            Some(error) if error == crate::run::TIMEOUT => (None, crate::run::TIMEOUT.to_string()),
+            Some(_) => (None, "FAILED".to_string()),
+        };
+        Timing { timestamp, commit: commit.to_string(), day: record.day, part: record.part, duration_ms, status }
+    }
+
+    // one tab separated line: timestamp, commit, day, part, duration in ms (- if failed) and status
+    fn parse(line: &str) -> Option<Timing> {
+        let mut fields = line.split('\t');
+        let timing = Timing {
+            timestamp: fields.next()?.parse().ok()?,
+            commit: fields.next()?.to_string(),
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+            duration_ms: match fields.next()? {
+                "-" => None,
+                duration => Some(duration.parse().ok()?),
+            },
+            status: fields.next()?.to_string(),
+        };
+        Some(timing)
+    }
+
+    fn to_line(&self) -> String {
+        let duration = self.duration_ms.map_or("-".to_string(), |duration| format!("{:.3}", duration));
+        format!("{}\t{}\t{}\t{}\t{}\t{}\n", self.timestamp, self.commit, self.day, self.part, duration, self.status)
+    }
+
+    fn cell(&self) -> String {
+        self.duration_ms.map_or(self.status.clone(), |duration| format!("{:.3}", duration))
+    }
+}
+
+// the checked out commit, marked -dirty if there are uncommitted changes
+fn current_commit() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or("unknown".to_string(), |commit| commit.trim().to_string())
+}
+
+// yyyy-mm-dd hh:mm in utc, from the days since 1970 as in howard hinnant's civil_from_days
+fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = ((timestamp / 86400) as i64, timestamp % 86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, seconds / 3600, seconds % 3600 / 60)
+}
+
+fn append_to(path: &Path, records: &[Record], timestamp: u64, commit: &str) -> Result<(), String> {
+    use std::io::Write;
+    let lines: String = records.iter().map(|record| Timing::from_record(record, timestamp, commit).to_line()).collect();
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|error| format!("Could not write {}: {}", path.display(), error))
+}
+
+// appends the timings of one run to the history
+pub(crate) fn record(records: &[Record]) -> Result<String, String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let commit = current_commit();
+    append_to(Path::new(HISTORY_FILE), records, timestamp, &commit)?;
+    Ok(format!("Added the timings of {} to {}", commit, HISTORY_FILE))
+}
+
+// the last runs side by side, the change is between the last two runs that solved the challenge
+// threshold is in percent, a slowdown beyond it or a challenge that stopped working is a regression
+fn report_from(timings: &[Timing], threshold: f64) -> String {
+    let mut runs: Vec<(u64, &str)> = Vec::new();
+    for timing in timings {
+        if !runs.contains(&(timing.timestamp, timing.commit.as_str())) {
+            runs.push((timing.timestamp, &timing.commit));
+        }
+    }
+    if runs.is_empty() {
+        return format!("There are no timings yet, run bench to add some to {}\n", HISTORY_FILE);
+    }
+    let runs = &runs[runs.len().saturating_sub(REPORT_RUNS)..];
+    let mut challenges: Vec<(u8, u8)> = timings.iter().map(|timing| (timing.day, timing.part)).collect();
+    challenges.sort();
+    challenges.dedup();
+    let width = runs.iter().map(|(_, commit)| commit.len()).max().unwrap().max(9);
+    let mut output = format!(
+        "Timings in ms of the last {} runs, from {} ({}) to {} ({})\n{:<9}",
+        runs.len(),
+        runs[0].1,
+        format_timestamp(runs[0].0),
+        runs[runs.len() - 1].1,
+        format_timestamp(runs[runs.len() - 1].0),
+        "challenge"
+    );
+    for (_, commit) in runs {
+        output.push_str(&format!("  {:>width$}", commit, width = width));
+    }
+    output.push_str("  change\n");
+    let mut regressions = 0;
+    for &(day, part) in &challenges {
+        let cells: Vec<Option<&Timing>> = runs
+            .iter()
+            .map(|&(timestamp, commit)| {
+                timings.iter().find(|t| t.timestamp == timestamp && t.commit == commit && t.day == day && t.part == part)
+            })
+            .collect();
+        output.push_str(&format!("{:<9}", format!("{}-{}", day, part)));
+        for cell in &cells {
+            output.push_str(&format!("  {:>width$}", cell.map_or(String::new(), Timing::cell), width = width));
+        }
+        let latest = cells.last().unwrap();
+        let previous = cells[..cells.len() - 1].iter().rev().flatten().find(|timing| timing.duration_ms.is_some());
+        let (change, regression) = match (latest.and_then(|t| t.duration_ms), previous.and_then(|t| t.duration_ms)) {
+            (Some(latest), Some(previous)) => {
+                let change = (latest - previous) / previous * 100.0;
+                (format!("{:+.1}%", change), change > threshold && latest >= NOISE_FLOOR_MS)
+            }
+            (None, Some(_)) if latest.is_some() => ("stopped working".to_string(), true),
+            _ => (String::new(), false),
+        };
+        output.push_str(&format!("  {}", change));
+        if regression {
+            regressions += 1;
+            output.push_str("  REGRESSION");
+        }
+        output = output.trim_end().to_string();
+        output.push('\n');
+    }
+    output.push_str(&match regressions {
+        0 => format!("No regressions beyond {}%\n", threshold),
+        1 => format!("1 regression beyond {}%\n", threshold),
+        n => format!("{} regressions beyond {}%\n", n, threshold),
+    });
+    output
+}
+
+pub(crate) fn report(threshold: f64) -> String {
+    let contents = std::fs::read_to_string(HISTORY_FILE).unwrap_or_default();
+    let timings: Vec<Timing> = contents.lines().filter_map(Timing::parse).collect();
+    report_from(&timings, threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::TempDir;
+
+    fn timing(timestamp: u64, commit: &str, day: u8, duration_ms: Option<f64>) -> Timing {
+        let status = if duration_ms.is_some() { "ok" } else { "TIMEOUT" };
+        Timing { timestamp, commit: commit.to_string(), day, part: 1, duration_ms, status: status.to_string() }
+    }
+
+    #[test]
+    fn test_timing_line() {
+        for timing in [timing(1700000000, "e8b6f4c-dirty", 9, Some(1.5)), timing(1, "unknown", 5, None)] {
+            assert_eq!(Timing::parse(timing.to_line().trim_end()), Some(timing));
+        }
+        assert_eq!(Timing::parse("1\tabc\t9"), None);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1700000000), "2023-11-14 22:13");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00");
+    }
+
+    #[test]
+    fn test_report() {
+        let timings = [
+            timing(100, "aaaaaaa", 1, Some(10.0)),
+            timing(100, "aaaaaaa", 2, Some(10.0)),
+            timing(100, "aaaaaaa", 3, Some(0.1)),
+            timing(100, "aaaaaaa", 4, Some(10.0)),
+            timing(200, "bbbbbbb", 1, Some(10.5)),
+            timing(200, "bbbbbbb", 2, Some(20.0)),
+            timing(200, "bbbbbbb", 3, Some(0.5)),
+            timing(200, "bbbbbbb", 4, None),
+        ];
+        let report = report_from(&timings, 10.0);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[1], "challenge    aaaaaaa    bbbbbbb  change");
+        assert_eq!(lines[2], "1-1           10.000     10.500  +5.0%");
+        assert_eq!(lines[3], "2-1           10.000     20.000  +100.0%  REGRESSION");
+        // too fast to tell
+        assert_eq!(lines[4], "3-1            0.100      0.500  +400.0%");
+        assert_eq!(lines[5], "4-1           10.000    TIMEOUT  stopped working  REGRESSION");
+        assert_eq!(lines[6], "2 regressions beyond 10%");
+        assert!(report_from(&[], 10.0).starts_with("There are no timings yet"));
+    }
+
+    #[test]
+    fn test_append_to() {
+        let dir = TempDir::new("bench");
+        let path = dir.join("timings.txt");
+        let records = [crate::run::run(30, 1, None, None)];
+        append_to(&path, &records, 100, "aaaaaaa").unwrap();
+        append_to(&path, &records, 200, "bbbbbbb").unwrap();
+        let timings: Vec<Timing> = std::fs::read_to_string(&path).unwrap().lines().filter_map(Timing::parse).collect();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[1].commit, "bbbbbbb");
+        assert_eq!(timings[1].status, "FAILED");
+    }
+}
@@ -1,42 +1,178 @@
-use regex::Regex;
+use crate::run::escape_json;
 
+// spelled out digits, every digit character always counts as well
+#[derive(Debug, Clone)]
+pub(crate) struct DigitWords {
+    words: Vec<(String, u32)>,
+}
 
-fn word_to_digit(word: &str) -> u32 {
-match word {
-        "one" | "1" => 1,
-        "two" | "2" => 2,
-        "three" | "3" => 3,
-        "four" | "4" => 4,
-        "five" | "5" => 5,
-        "six" | "6" => 6,
-        "seven" | "7" => 7,
-        "eight" | "8" => 8,
-        "nine" | "9" => 9,
-        _ => panic!("This is not a digit!"),
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ExplainFormat {
+    Table,
+    Json,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Hit<'a> {
+    offset: usize,
+    text: &'a str,
+    value: u32,
+}
 
-pub(crate) fn c1(input: String) -> String {
+impl DigitWords {
+    pub(crate) fn new() -> DigitWords {
+        DigitWords {
+            words: Vec::new(),
+        }
+    }
+
+    pub(crate) fn english() -> DigitWords {
+        let mut words = DigitWords::new();
+        for (i, word) in ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"].iter().enumerate() {
+            words.add_word(word, i as u32 + 1);
+        }
+        words
+    }
+
+    pub(crate) fn add_word(&mut self, word: &str, value: u32) {
+        self.words.push((word.to_string(), value));
+    }
+
+    // all digits and words in the line, in order and including overlapping ones like "twone"
+    fn scan<'a>(&'a self, line: &'a str) -> impl Iterator<Item=Hit<'a>> + 'a {
+        line.char_indices().filter_map(move |(offset, c)| {
+            if let Some(value) = c.to_digit(10) {
+                return Some(Hit { offset, text: &line[offset..offset + 1], value });
+            }
+            self.words
+                .iter()
+                .find(|(word, _)| line[offset..].starts_with(word.as_str()))
+                .map(|(word, value)| Hit { offset, text: &line[offset..offset + word.len()], value: *value })
+        })
+    }
+
+    // the first and the last hit of a single forward scan
+    fn first_last<'a>(&'a self, line: &'a str) -> Option<(Hit<'a>, Hit<'a>)> {
+        self.scan(line).fold(None, |acc, hit| match acc {
+            None => Some((hit, hit)),
+            Some((first, _)) => Some((first, hit)),
+        })
+    }
+}
+
+fn calibration_sum(input: &str, words: &DigitWords) -> u32 {
     let mut sum = 0;
     for line in input.lines() {
-        let first_digit = line.chars().find(|c| c.is_digit(10)).unwrap();
-        let last_digit = line.chars().rfind(|c| c.is_digit(10)).unwrap();
-        sum += first_digit.to_digit(10).unwrap() * 10 + last_digit.to_digit(10).unwrap();
+        let (first, last) = words.first_last(line).expect("This line has no digits!");
+        sum += first.value * 10 + last.value;
     }
-    sum.to_string()
+    sum
+}
+
+fn hit_json(hit: Option<Hit>) -> String {
+    match hit {
+        Some(hit) => format!("{{\"text\": \"{}\", \"offset\": {}}}", escape_json(hit.text), hit.offset),
+        None => "null".to_string(),
+    }
+}
+
+// which substrings were picked as the first and last digit of every line, and what they add up to
+fn explain_lines(input: &str, words: &DigitWords, format: ExplainFormat) -> String {
+    let mut output = match format {
+        ExplainFormat::Table => format!("{:>5}  {:<8}{:>4}  {:<8}{:>4}  {:>5}  text\n", "line", "first", "at", "last", "at", "value"),
+        ExplainFormat::Json => "[\n".to_string(),
+    };
+    for (index, line) in input.lines().enumerate() {
+        let hits = words.first_last(line);
+        let value = hits.map(|(first, last)| first.value * 10 + last.value);
+        match format {
+            ExplainFormat::Table => {
+                let (first, last) = match hits {
+                    Some((first, last)) => (
+                        format!("{:<8}{:>4}", first.text, first.offset),
+                        format!("{:<8}{:>4}", last.text, last.offset),
+                    ),
+                    None => (format!("{:<8}{:>4}", "-", "-"), format!("{:<8}{:>4}", "-", "-")),
+                };
+                let value = value.map_or("-".to_string(), |v| v.to_string());
+                output.push_str(&format!("{:>5}  {}  {}  {:>5}  {}\n", index + 1, first, last, value, line));
+            }
+            ExplainFormat::Json => {
+                if index > 0 {
+                    output.push_str(",\n");
+                }
+                output.push_str(&format!(
+                    "  {{\"line\": {}, \"text\": \"{}\", \"first\": {}, \"last\": {}, \"value\": {}}}",
+                    index + 1,
+                    escape_json(line),
+                    hit_json(hits.map(|(first, _)| first)),
+                    hit_json(hits.map(|(_, last)| last)),
+                    value.map_or("null".to_string(), |v| v.to_string()),
+                ));
+            }
+        }
+    }
+    if format == ExplainFormat::Json {
+        output.push_str("\n]\n");
+    }
+    output
+}
+
+pub(crate) fn explain(input: String, challenge: u8, format: ExplainFormat) -> String {
+    let words = if challenge == 1 { DigitWords::new() } else { DigitWords::english() };
+    explain_lines(&input, &words, format)
+}
+
+pub(crate) fn c1(input: String) -> String {
+    calibration_sum(&input, &DigitWords::new()).to_string()
 }
 
 pub(crate) fn c2(input: String) -> String {
-    let re_f = Regex::new(r"\d|one|two|three|four|five|six|seven|eight|nine").unwrap();
-    let re_b = Regex::new(&(r"d\|one|two|three|four|five|six|seven|eight|nine").chars().rev().collect::<String>()).unwrap();
-    let mut sum:u32 = 0;
-    for line in input.lines() {
-        let first_digit = word_to_digit(re_f.find(line).unwrap().as_str());
-        let rev_line: String = line.chars().rev().collect();
-        let last_digit = word_to_digit(re_b.find(&rev_line).unwrap().as_str().chars().rev().collect::<String>().as_str());
-        sum += first_digit * 10 + last_digit;
-        println!("{}{} - {}", first_digit, last_digit, line);
+    calibration_sum(&input, &DigitWords::english()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        assert_eq!(c1(input.to_string()), "142");
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+        assert_eq!(c2(input.to_string()), "281");
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let words = DigitWords::english();
+        assert_eq!(calibration_sum("twone", &words), 21);
+        assert_eq!(calibration_sum("eightwo", &words), 82);
+        assert_eq!(calibration_sum("oneight", &words), 18);
+    }
+
+    #[test]
+    fn test_other_words() {
+        let mut words = DigitWords::new();
+        for (i, word) in ["null", "eins", "zwei", "drei"].iter().enumerate() {
+            words.add_word(word, i as u32);
+        }
+        assert_eq!(calibration_sum("zweinsnull", &words), 20);
+        assert_eq!(calibration_sum("x3dreinsx", &words), 31);
+    }
+
+    #[test]
+    fn test_explain() {
+        let words = DigitWords::english();
+        assert_eq!(explain_lines("xtwone3four\nabc", &words, ExplainFormat::Table), concat!(
+            " line  first     at  last      at  value  text\n",
+            "    1  two        1  four       7     24  xtwone3four\n",
+            "    2  -          -  -          -      -  abc\n",
+        ));
+        assert_eq!(explain_lines("xtwone3four\nabc", &words, ExplainFormat::Json), r#"[
+  {"line": 1, "text": "xtwone3four", "first": {"text": "two", "offset": 1}, "last": {"text": "four", "offset": 7}, "value": 24},
+  {"line": 2, "text": "abc", "first": null, "last": null, "value": null}
+]
+"#);
     }
-    sum.to_string()
 }
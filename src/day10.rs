@@ -95,6 +95,32 @@ impl Field {
         }
     }
 
+    fn glyph(&self, heavy: bool) -> char {
+        let connections = (
+            self.connects_to(Direction::North),
+            self.connects_to(Direction::South),
+            self.connects_to(Direction::East),
+            self.connects_to(Direction::West),
+        );
+        match (self, connections, heavy) {
+            (Field::Start, _, _) => 'S',
+            (Field::Empty, _, _) => '·',
+            (_, (true, true, false, false), false) => '│',
+            (_, (true, true, false, false), true) => '┃',
+            (_, (false, false, true, true), false) => '─',
+            (_, (false, false, true, true), true) => '━',
+            (_, (true, false, false, true), false) => '┘',
+            (_, (true, false, false, true), true) => '┛',
+            (_, (true, false, true, false), false) => '└',
+            (_, (true, false, true, false), true) => '┗',
+            (_, (false, true, false, true), false) => '┐',
+            (_, (false, true, false, true), true) => '┓',
+            (_, (false, true, true, false), false) => '┌',
+            (_, (false, true, true, false), true) => '┏',
+            _ => panic!("Invalid pipe"),
+        }
+    }
+
     fn get_left_right(&self, out_dir: Direction) -> (Vec<Direction>, Vec<Direction>) {
         let mut _right_mode = true;
         let mut left = Vec::new();
@@ -123,19 +149,67 @@ impl Field {
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 struct Position(usize, usize);
 
-struct Graph {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum InsideMethod {
+    // flood fill starting from the tiles on the inner side of the loop
+    SideFill,
+    // shoelace formula over the loop vertices combined with pick's theorem
+    Shoelace,
+    // flood fill on a 3x3 upsampled grid, so it can squeeze between pipes
+    Squeeze,
+}
+
+impl InsideMethod {
+    pub(crate) fn parse(method: &str) -> Option<InsideMethod> {
+        match method {
+            "side" => Some(InsideMethod::SideFill),
+            "shoelace" => Some(InsideMethod::Shoelace),
+            "squeeze" => Some(InsideMethod::Squeeze),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tile {
+    Loop,
+    Inside,
+    Outside,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum RenderFormat {
+    // box-drawing characters with ansi colours, for the terminal
+    Ansi,
+    // box-drawing characters with I and O marking inside and outside
+    Text,
+    Svg,
+}
+
+pub(crate) struct Graph {
     data: Vec<Vec<Field>>,
-    _start: Option<Position>,
-    _start_directions: Option<(Direction, Direction)>,
+    start: Option<Position>,
+    start_directions: Option<(Direction, Direction)>,
 }
 
 impl Graph {
     fn new() -> Graph {
         Graph {
             data: Vec::new(),
-            _start: None,
-            _start_directions: None,
+            start: None,
+            start_directions: None,
+        }
+    }
+
+    pub(crate) fn parse(input: &str) -> Graph {
+        let mut graph = Graph::new();
+        for line in input.lines() {
+            graph.add_row(line.chars().map(Field::new).collect());
+        }
+        if let Err(directions) = graph.replace_start() {
+            panic!("The start tile connects to {:?}, it has to connect to exactly two pipes", directions);
         }
+        graph
     }
 
     fn add_row(&mut self, row: Vec<Field>) {
@@ -153,6 +227,30 @@ impl Graph {
         panic!("No start found");
     }
 
+    // the start pipe is the one that connects to exactly those neighbours which connect back to it
+    // on failure, all directions with a connecting neighbour are returned
+    fn infer_start(&self, start: Position) -> Result<(Direction, Direction), Vec<Direction>> {
+        let directions: Vec<Direction> = [Direction::North, Direction::South, Direction::East, Direction::West]
+            .into_iter()
+            .filter(|&dir| self.slide(start, dir)
+                .is_some_and(|neighbour| self[neighbour].connects_to(dir.rev())))
+            .collect();
+        match directions[..] {
+            [d1, d2] => Ok((d1, d2)),
+            _ => Err(directions),
+        }
+    }
+
+    // swap the start tile for the pipe it hides
+    fn replace_start(&mut self) -> Result<(), Vec<Direction>> {
+        let start = self.find_start();
+        let (d1, d2) = self.infer_start(start)?;
+        self.data[start.1][start.0] = Field::Pipe(d1, d2);
+        self.start = Some(start);
+        self.start_directions = Some((d1, d2));
+        Ok(())
+    }
+
     fn slide(&self, pos: Position, dir: Direction) -> Option<Position> {
         // get direction as vector
         let (dx, dy) = dir.resolve();
@@ -203,10 +301,10 @@ impl Graph {
                 }
             }
             // collect left and right turns
-            if node.connects_to(dir.left()) && node != Field::Start {
+            if node.connects_to(dir.left()) {
                 left += 1;
             }
-            if node.connects_to(dir.right()) && node != Field::Start {
+            if node.connects_to(dir.right()) {
                 right += 1;
             }
             // add pos to path
@@ -214,103 +312,229 @@ impl Graph {
             // move to next pos
             pos = next_pos;
             node = self[pos];
-            dir = node.move_from_to(dir.rev()).unwrap_or(start_direction);
-            if let Field::Start = self[pos] {
+            dir = node.move_from_to(dir.rev()).unwrap();
+            if pos == start {
                 return Some((path, if left > right { left_nodes } else { right_nodes }));
             }
         }
         None
     }
 
-}
-
-impl Index<Position> for Graph {
-    type Output = Field;
+    fn find_loop(&self) -> (Vec<Position>, Vec<Position>) {
+        let start = self.start.expect("The start tile has not been resolved");
+        let (dir, _) = self.start_directions.unwrap();
+        self.find_path(start, dir).expect("No loop found")
+    }
 
-    fn index(&self, index: Position) -> &Self::Output {
-        &self.data[index.1][index.0]
+    fn count_inside(&self, method: InsideMethod) -> usize {
+        let (path, inside) = self.find_loop();
+        match method {
+            InsideMethod::SideFill => self.side_fill(&path, inside),
+            InsideMethod::Shoelace => shoelace(&path),
+            InsideMethod::Squeeze => self.squeeze_fill(&path),
+        }
     }
-}
 
-pub(crate) fn c1(input: String) -> String {
-    let mut graph = Graph::new();
-    for line in input.lines() {
-        graph.add_row(line.chars().map(|c| Field::new(c)).collect());
-    }
-    // go from start field and find the loop
-    let directions = vec![Direction::North, Direction::South, Direction::East, Direction::West];
-    for direction in directions {
-        let mut pos = graph.find_start();
-        // try to take the first step
-        if let Some(next) = graph.step(pos, direction) {
-            pos = next;
-        } else {
-            continue;
+    // blow every tile up to 3x3 cells so the gaps between parallel pipes become walkable,
+    // then flood the outside from the border and count the tiles whose center was never reached
+    fn squeeze_fill(&self, path: &[Position]) -> usize {
+        let (width, height) = (self.data[0].len() * 3, self.data.len() * 3);
+        // only the main loop blocks, all other pipes are as good as empty tiles
+        let mut blocked = vec![false; width * height];
+        for &pos in path {
+            let center = (pos.0 * 3 + 1, pos.1 * 3 + 1);
+            blocked[center.1 * width + center.0] = true;
+            if let Field::Pipe(d1, d2) = self[pos] {
+                for (dx, dy) in [d1.resolve(), d2.resolve()] {
+                    let (x, y) = ((center.0 as i32 + dx) as usize, (center.1 as i32 + dy) as usize);
+                    blocked[y * width + x] = true;
+                }
+            }
         }
-        let mut last_direction = direction;
-        let mut steps = 1;
-        loop {
-            // try to take a step in a new direction
-            let new_direction = graph[pos].move_from_to(last_direction.rev());
-            if let Some(new_direction) = new_direction {
-                if let Some(next) = graph.step(pos, new_direction) {
-                    pos = next;
-                    last_direction = new_direction;
-                    steps += 1;
-                } else {
-                    break;
+        // the top left corner cell is never blocked, since pipes only cover the center and the edges
+        let mut visited = vec![false; width * height];
+        visited[0] = true;
+        let mut q: Vec<(usize, usize)> = vec![(0, 0)];
+        while let Some((x, y)) = q.pop() {
+            for (nx, ny) in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+                if nx < width && ny < height && !visited[ny * width + nx] && !blocked[ny * width + nx] {
+                    visited[ny * width + nx] = true;
+                    q.push((nx, ny));
                 }
-            } else {
-                break;
-
             }
         }
-        // if pos is the start pos, we found the loop
-        if pos == graph.find_start() {
-            return (steps/2).to_string();
+        (0..self.data.len())
+            .cartesian_product(0..self.data[0].len())
+            .map(|(y, x)| (y * 3 + 1) * width + x * 3 + 1)
+            .filter(|&center| !visited[center] && !blocked[center])
+            .count()
+    }
+
+    // scan every row and flip between outside and inside whenever a loop pipe reaching north is crossed
+    fn classify(&self) -> Vec<Vec<Tile>> {
+        let (path, _) = self.find_loop();
+        let mut tiles = vec![vec![Tile::Outside; self.data[0].len()]; self.data.len()];
+        for pos in path {
+            tiles[pos.1][pos.0] = Tile::Loop;
+        }
+        for (y, row) in tiles.iter_mut().enumerate() {
+            let mut inside = false;
+            for (x, tile) in row.iter_mut().enumerate() {
+                if *tile == Tile::Loop {
+                    if self.data[y][x].connects_to(Direction::North) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    *tile = Tile::Inside;
+                }
+            }
         }
+        tiles
     }
-    panic!("No loop found");
-}
 
+    // what the tile in column x and row y is, counted from 0, and how it lies to the loop
+    pub(crate) fn describe_tile(&self, x: usize, y: usize) -> Option<String> {
+        let field = self.data.get(y)?.get(x)?;
+        let tile = match self.classify()[y][x] {
+            Tile::Loop => "part of the loop",
+            Tile::Inside => "inside the loop",
+            Tile::Outside => "outside the loop",
+        };
+        let start = if self.start == Some(Position(x, y)) { ", the start" } else { "" };
+        Some(format!("{} {}{}", field.glyph(false), tile, start))
+    }
 
-pub(crate) fn c2(input: String) -> String {
-    let mut graph = Graph::new();
-    for line in input.lines() {
-        graph.add_row(line.chars().map(|c| Field::new(c)).collect());
-    }
-    let start = graph.find_start();
-    let mut result = None;
-    let mut directions = vec![Direction::North, Direction::South, Direction::East, Direction::West];
-    while result == None && directions.len() > 0 {
-        let dir = directions.pop().unwrap();
-        result = graph.find_path(start, dir);
-    }
-    let (path, inside) = result.unwrap();
-    let mut q: Vec<Position> = inside
-        .into_iter()
-        .filter(|pos| !path.contains(pos))
-        .unique()
-        .collect();
-    // make more inner nodes that touch
-    let mut inner = Vec::new();
-    while let Some(pos) = q.pop() {
-        let neighbours: Vec<Position> = vec![Direction::North, Direction::South, Direction::East, Direction::West]
+    fn render_text(&self, ansi: bool) -> String {
+        let tiles = self.classify();
+        let mut output = String::new();
+        for (fields, tiles) in self.data.iter().zip(tiles) {
+            for (field, tile) in fields.iter().zip(tiles) {
+                match (tile, ansi) {
+                    (Tile::Loop, false) => output.push(field.glyph(true)),
+                    (Tile::Loop, true) => output.push_str(&format!("\x1b[1;33m{}\x1b[0m", field.glyph(true))),
+                    (Tile::Inside, false) => output.push('I'),
+                    (Tile::Inside, true) => output.push_str(&format!("\x1b[30;42m{}\x1b[0m", field.glyph(false))),
+                    (Tile::Outside, false) => output.push('O'),
+                    (Tile::Outside, true) => output.push_str(&format!("\x1b[2m{}\x1b[0m", field.glyph(false))),
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    fn render_svg(&self) -> String {
+        const SIZE: usize = 8;
+        let tiles = self.classify();
+        let (width, height) = (self.data[0].len() * SIZE, self.data.len() * SIZE);
+        let mut output = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        output.push_str(&format!("<rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n"));
+        // inside tiles as filled squares, all other pipes as thin lines
+        let mut pipes = String::new();
+        for (y, (fields, tiles)) in self.data.iter().zip(tiles).enumerate() {
+            for (x, (field, tile)) in fields.iter().zip(tiles).enumerate() {
+                if tile == Tile::Inside {
+                    output.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{SIZE}\" height=\"{SIZE}\" fill=\"#9be39b\"/>\n",
+                        x * SIZE, y * SIZE
+                    ));
+                }
+                if let (Field::Pipe(d1, d2), Tile::Inside | Tile::Outside) = (field, tile) {
+                    let center = (x * SIZE + SIZE / 2, y * SIZE + SIZE / 2);
+                    for (dx, dy) in [d1.resolve(), d2.resolve()] {
+                        pipes.push_str(&format!(
+                            "M{} {} l{} {} ",
+                            center.0, center.1, dx * SIZE as i32 / 2, dy * SIZE as i32 / 2
+                        ));
+                    }
+                }
+            }
+        }
+        output.push_str(&format!("<path d=\"{}\" stroke=\"#bbbbbb\" stroke-width=\"1\"/>\n", pipes.trim_end()));
+        // the main loop as one closed polygon through the tile centers
+        let (path, _) = self.find_loop();
+        let points = path
             .iter()
-            .map(|dir| graph.slide(pos, *dir))
-            .filter(|pos| pos.is_some())
-            .map(|pos| pos.unwrap())
+            .map(|pos| format!("{},{}", pos.0 * SIZE + SIZE / 2, pos.1 * SIZE + SIZE / 2))
+            .join(" ");
+        output.push_str(&format!("<polygon points=\"{points}\" fill=\"none\" stroke=\"#d33\" stroke-width=\"2\"/>\n"));
+        output.push_str("</svg>\n");
+        output
+    }
+
+    fn side_fill(&self, path: &[Position], inside: Vec<Position>) -> usize {
+        let mut q: Vec<Position> = inside
+            .into_iter()
+            .filter(|pos| !path.contains(pos))
+            .unique()
             .collect();
-        for neighbour in neighbours {
-            if !path.contains(&neighbour) && !inner.contains(&neighbour) && !q.contains(&neighbour) {
-                q.push(neighbour);
+        // make more inner nodes that touch
+        let mut inner = Vec::new();
+        while let Some(pos) = q.pop() {
+            let neighbours: Vec<Position> = [Direction::North, Direction::South, Direction::East, Direction::West]
+                .iter()
+                .filter_map(|dir| self.slide(pos, *dir))
+                .collect();
+            for neighbour in neighbours {
+                if !path.contains(&neighbour) && !inner.contains(&neighbour) && !q.contains(&neighbour) {
+                    q.push(neighbour);
+                }
             }
+            inner.push(pos);
         }
-        inner.push(pos);
+        inner.len()
     }
 
+}
+
+// the loop area by the shoelace formula, then pick's theorem (A = i + b/2 - 1)
+// gives the number of tiles strictly inside the loop
+fn shoelace(path: &[Position]) -> usize {
+    let twice_area = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(p1, p2)| p1.0 as i64 * p2.1 as i64 - p2.0 as i64 * p1.1 as i64)
+        .sum::<i64>()
+        .unsigned_abs() as usize;
+    // pick's theorem: a = i + b/2 - 1, so i = (2a + 2 - b) / 2
+    (twice_area + 2 - path.len()) / 2
+}
 
-    inner.iter().count().to_string()
+impl Index<Position> for Graph {
+    type Output = Field;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.data[index.1][index.0]
+    }
+}
+
+pub(crate) fn c1(input: String) -> String {
+    let graph = Graph::parse(&input);
+    // the farthest point is halfway along the loop
+    let (path, _) = graph.find_loop();
+    (path.len() / 2).to_string()
+}
+
+pub(crate) fn c2(input: String) -> String {
+    let graph = Graph::parse(&input);
+    graph.count_inside(InsideMethod::Shoelace).to_string()
+}
+
+// count the tiles enclosed by the loop with the given method, to compare them against each other
+pub(crate) fn analyze(input: String, method: InsideMethod) -> String {
+    let graph = Graph::parse(&input);
+    format!("Inside ({:?}): {}\n", method, graph.count_inside(method))
+}
+
+pub(crate) fn render(input: String, format: RenderFormat) -> String {
+    let graph = Graph::parse(&input);
+    match format {
+        RenderFormat::Ansi => graph.render_text(true),
+        RenderFormat::Text => graph.render_text(false),
+        RenderFormat::Svg => graph.render_svg(),
+    }
 }
 
 #[cfg(test)]
@@ -332,4 +556,88 @@ mod tests {
         assert_eq!(r, vec![Direction::North]);
         assert_eq!(l, vec![Direction::South]);
     }
-}
\ No newline at end of file
+
+    const EXAMPLE_1: &str = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+    const EXAMPLE_2: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+    const EXAMPLE_3: &str = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+
+    #[test]
+    fn test_inside_methods_agree() {
+        for (input, expected) in [(EXAMPLE_1, 4), (EXAMPLE_2, 8), (EXAMPLE_3, 4), ("S7\nLJ", 0)] {
+            let graph = Graph::parse(input);
+            assert_eq!(graph.count_inside(InsideMethod::Shoelace), expected);
+            assert_eq!(graph.count_inside(InsideMethod::SideFill), expected);
+            assert_eq!(graph.count_inside(InsideMethod::Squeeze), expected);
+        }
+    }
+
+    #[test]
+    fn test_infer_start() {
+        let graph = Graph::parse(EXAMPLE_1);
+        assert_eq!(graph.start, Some(Position(1, 1)));
+        assert_eq!(graph[Position(1, 1)], Field::new('F'));
+        let graph = Graph::parse(EXAMPLE_2);
+        assert_eq!(graph[Position(12, 4)], Field::new('F'));
+    }
+
+    #[test]
+    fn test_infer_start_ambiguous() {
+        let mut graph = Graph::new();
+        for line in ".|.\n-S-\n.|.".lines() {
+            graph.add_row(line.chars().map(Field::new).collect());
+        }
+        assert_eq!(
+            graph.infer_start(Position(1, 1)),
+            Err(vec![Direction::North, Direction::South, Direction::East, Direction::West])
+        );
+        assert!(graph.replace_start().is_err());
+    }
+
+    #[test]
+    fn test_render_text() {
+        let graph = Graph::parse(EXAMPLE_3);
+        assert_eq!(graph.render_text(false), "\
+OOOOOOOOOO
+O┏━━━━━━┓O
+O┃┏━━━━┓┃O
+O┃┃OOOO┃┃O
+O┃┃OOOO┃┃O
+O┃┗━┓┏━┛┃O
+O┃II┃┃II┃O
+O┗━━┛┗━━┛O
+OOOOOOOOOO
+");
+        for example in [EXAMPLE_1, EXAMPLE_2, EXAMPLE_3] {
+            let graph = Graph::parse(example);
+            let inside = graph.render_text(false).chars().filter(|&c| c == 'I').count();
+            assert_eq!(inside, graph.count_inside(InsideMethod::Shoelace));
+        }
+    }
+}
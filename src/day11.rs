@@ -1,19 +1,43 @@
 use std::cmp::max;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Galaxy (usize, usize);
+pub(crate) struct Galaxy (pub(crate) usize, pub(crate) usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Metric {
+    Manhattan,
+    Chebyshev,
+    EuclideanSquared,
+}
 
 #[derive(Debug)]
-struct Universe {
+pub(crate) struct Universe {
+    // in reading order, so galaxy n is galaxies[n-1]
     galaxies: Vec<Galaxy>,
-    size: (usize, usize)
+    size: (usize, usize),
+    factor: usize,
+    metric: Metric,
 }
 
 impl Universe {
     fn new() -> Self {
         Universe {
             galaxies: Vec::new(),
-            size: (0, 0)
+            size: (0, 0),
+            factor: 1,
+            metric: Metric::Manhattan,
+        }
+    }
+
+    pub(crate) fn parse(input: &str) -> Self {
+        let mut universe = Universe::new();
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if c == '#' {
+                    universe.add_galaxy(Galaxy(x, y));
+                }
+            }
         }
+        universe
     }
 
     fn add_galaxy(&mut self, galaxy: Galaxy) {
@@ -26,87 +50,206 @@ impl Universe {
         self.size = size;
     }
 
-    // factor is not really a factor, it just adds the amount of extra space
-    // to use it as a factor, use factor-1
-    fn expand(&mut self, factor: usize) {
-        let mut empty = (Vec::new(), Vec::new());
-        // find all unused y values for empty space
-        for x in 0..self.size.0 {
-            if self.galaxies
-                .iter()
-                .filter(|g| g.0 == x)
-                .count() == 0 {
-                    empty.0.push(x);
-                }
-        }
-        for y in 0..self.size.1 {
-            if self.galaxies
-                .iter()
-                .filter(|g| g.1 == y)
-                .count() == 0 {
-                empty.1.push(y);
-            }
-        }
-        // move galaxies
-        for x in empty.0.iter().rev() {
-            self.galaxies
-                .iter_mut()
-                .filter(|g| g.0 > *x)
-                .for_each(|g| g.0 += factor);
-        }
-        for y in empty.1.iter().rev() {
-            self.galaxies
-                .iter_mut()
-                .filter(|g| g.1 > *y)
-                .for_each(|g| g.1 += factor);
-        }
-        // update size
-        self.size.0 += empty.0.len() * factor;
-        self.size.1 += empty.1.len() * factor;
+    // every empty row and column is replaced by factor empty rows or columns
+    pub(crate) fn set_expansion(&mut self, factor: usize) {
+        self.factor = factor;
+    }
+
+    // the positions of all galaxies after the expansion, None on overflow
+    fn expanded(&self) -> Option<Vec<(i128, i128)>> {
+        let x = expand_axis(self.galaxies.iter().map(|g| g.0), self.size.0, self.factor)?;
+        let y = expand_axis(self.galaxies.iter().map(|g| g.1), self.size.1, self.factor)?;
+        Some(x.into_iter().zip(y).collect())
     }
 
+    // sum of the distances of all galaxy pairs, None on overflow
+    pub(crate) fn distance_sum(&self) -> Option<usize> {
+        let expanded = self.expanded()?;
+        let sum = match self.metric {
+            Metric::Manhattan => pairwise_abs_sum(expanded.iter().map(|p| p.0).collect())?
+                .checked_add(pairwise_abs_sum(expanded.iter().map(|p| p.1).collect())?)?,
+            // rotated by 45 degrees, max(|dx|, |dy|) = (|dx + dy| + |dx - dy|) / 2
+            Metric::Chebyshev => pairwise_abs_sum(expanded.iter().map(|p| p.0.checked_add(p.1)).collect::<Option<_>>()?)?
+                .checked_add(pairwise_abs_sum(expanded.iter().map(|p| p.0.checked_sub(p.1)).collect::<Option<_>>()?)?)? / 2,
+            Metric::EuclideanSquared => pairwise_square_sum(expanded.iter().map(|p| p.0))?
+                .checked_add(pairwise_square_sum(expanded.iter().map(|p| p.1))?)?,
+        };
+        sum.try_into().ok()
+    }
 }
 
-pub(crate) fn c1(input: String) -> String {
-    let mut universe = Universe::new();
-    for (x, line) in input.lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            if c == '#' {
-                universe.add_galaxy(Galaxy(x, y));
-            }
-        }
+// queries for exploring a universe in the repl, the solutions only need the distance sum
+impl Universe {
+    pub(crate) fn set_metric(&mut self, metric: Metric) {
+        self.metric = metric;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.galaxies.len()
+    }
+
+    // galaxies are numbered from 1 in reading order, like in the puzzle
+    pub(crate) fn galaxy(&self, number: usize) -> Option<Galaxy> {
+        number.checked_sub(1).and_then(|i| self.galaxies.get(i)).copied()
+    }
+
+    fn measure(&self, a: (i128, i128), b: (i128, i128)) -> Option<usize> {
+        let (dx, dy) = (a.0.checked_sub(b.0)?.unsigned_abs(), a.1.checked_sub(b.1)?.unsigned_abs());
+        let distance = match self.metric {
+            Metric::Manhattan => dx.checked_add(dy)?,
+            Metric::Chebyshev => max(dx, dy),
+            Metric::EuclideanSquared => dx.checked_mul(dx)?.checked_add(dy.checked_mul(dy)?)?,
+        };
+        distance.try_into().ok()
+    }
+
+    // None if either galaxy does not exist or the distance overflows
+    pub(crate) fn distance(&self, a: usize, b: usize) -> Option<usize> {
+        self.galaxy(a)?;
+        self.galaxy(b)?;
+        let expanded = self.expanded()?;
+        self.measure(expanded[a - 1], expanded[b - 1])
     }
-    universe.expand(1);
-    // iterate over all galaxy pairs
-    // just count them double and half the result
-    let sum = universe.galaxies
-        .iter()
-        .map(|g1| universe.galaxies
+
+    // the k closest other galaxies as (number, distance), closest first and ties in reading order
+    pub(crate) fn nearest(&self, number: usize, k: usize) -> Option<Vec<(usize, usize)>> {
+        self.galaxy(number)?;
+        let expanded = self.expanded()?;
+        let from = expanded[number - 1];
+        let mut neighbours = expanded
             .iter()
-            .fold(0, |acc, g2| acc + (g1.0 as i32 - g2.0 as i32).abs() + (g1.1 as i32 - g2.1 as i32).abs())
-        )
-        .sum::<i32>() / 2;
+            .enumerate()
+            .filter(|&(i, _)| i + 1 != number)
+            .map(|(i, &to)| Some((i + 1, self.measure(from, to)?)))
+            .collect::<Option<Vec<(usize, usize)>>>()?;
+        neighbours.sort_by_key(|&(n, distance)| (distance, n));
+        neighbours.truncate(k);
+        Some(neighbours)
+    }
+}
+
+// move every coordinate by (factor - 1) for each empty line before it
+fn expand_axis(coordinates: impl Iterator<Item=usize> + Clone, size: usize, factor: usize) -> Option<Vec<i128>> {
+    let mut occupied = vec![false; size];
+    for c in coordinates.clone() {
+        occupied[c] = true;
+    }
+    let mut offsets = Vec::with_capacity(size);
+    let mut offset: i128 = 0;
+    for occupied in occupied {
+        offsets.push(offset);
+        if !occupied {
+            offset = offset.checked_add(factor as i128 - 1)?;
+        }
+    }
+    coordinates.map(|c| (c as i128).checked_add(offsets[c])).collect()
+}
+
+// sorted, every value is value - v away from each earlier value v,
+// which sums up to index * value - (sum of the earlier values)
+fn pairwise_abs_sum(mut values: Vec<i128>) -> Option<u128> {
+    values.sort();
+    let mut prefix: i128 = 0;
+    let mut sum: i128 = 0;
+    for (i, value) in values.into_iter().enumerate() {
+        sum = sum.checked_add(value.checked_mul(i as i128)?.checked_sub(prefix)?)?;
+        prefix = prefix.checked_add(value)?;
+    }
+    sum.try_into().ok()
+}
+
+// sum of (a - b)^2 over all pairs is n * sum(a^2) - sum(a)^2
+fn pairwise_square_sum(values: impl Iterator<Item=i128>) -> Option<u128> {
+    let (mut n, mut sum, mut squares): (i128, i128, i128) = (0, 0, 0);
+    for value in values {
+        n += 1;
+        sum = sum.checked_add(value)?;
+        squares = squares.checked_add(value.checked_mul(value)?)?;
+    }
+    n.checked_mul(squares)?.checked_sub(sum.checked_mul(sum)?)?.try_into().ok()
+}
+
+pub(crate) fn c1(input: String) -> String {
+    let mut universe = Universe::parse(&input);
+    universe.set_expansion(2);
+    let sum = universe.distance_sum().expect("Overflow while summing the distances!");
     sum.to_string()
 }
 
 pub(crate) fn c2(input: String) -> String {
-    let mut universe = Universe::new();
-    for (x, line) in input.lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            if c == '#' {
-                universe.add_galaxy(Galaxy(x, y));
+    let mut universe = Universe::parse(&input);
+    universe.set_expansion(1000000);
+    let sum = universe.distance_sum().expect("Overflow while summing the distances!");
+    sum.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    fn brute_force_sum(universe: &Universe) -> usize {
+        let mut sum = 0;
+        for a in 1..=universe.len() {
+            for b in a+1..=universe.len() {
+                sum += universe.distance(a, b).unwrap();
             }
         }
+        sum
     }
-    universe.expand(1000000-1);
-    // iterate over all galaxy pairs
-    // just count them double and half the result
-    let sum = universe.galaxies
-        .iter()
-        .map(|g1| universe.galaxies
-            .iter()
-            .fold(0, |acc, g2| acc + (g1.0 as i128 - g2.0 as i128).abs() + (g1.1 as i128 - g2.1 as i128).abs())
-        )
-        .sum::<i128>() / 2;
-    sum.to_string()
-}
\ No newline at end of file
+
+    #[test]
+    fn test_distance_sum() {
+        let mut universe = Universe::parse(EXAMPLE);
+        for (factor, expected) in [(2, 374), (10, 1030), (100, 8410)] {
+            universe.set_expansion(factor);
+            assert_eq!(universe.distance_sum(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let mut universe = Universe::parse(EXAMPLE);
+        universe.set_expansion(2);
+        for metric in [Metric::Manhattan, Metric::Chebyshev, Metric::EuclideanSquared] {
+            universe.set_metric(metric);
+            assert_eq!(universe.distance_sum(), Some(brute_force_sum(&universe)));
+        }
+        // galaxy 5 sits at (1, 6) and galaxy 9 at (5, 11) after the expansion
+        universe.set_metric(Metric::Chebyshev);
+        assert_eq!(universe.distance(5, 9), Some(5));
+        universe.set_metric(Metric::EuclideanSquared);
+        assert_eq!(universe.distance(5, 9), Some(41));
+    }
+
+    #[test]
+    fn test_pair_lookups() {
+        let mut universe = Universe::parse(EXAMPLE);
+        universe.set_expansion(2);
+        assert_eq!(universe.galaxy(1), Some(Galaxy(3, 0)));
+        assert_eq!(universe.distance(5, 9), Some(9));
+        assert_eq!(universe.distance(1, 7), Some(15));
+        assert_eq!(universe.distance(3, 6), Some(17));
+        assert_eq!(universe.distance(8, 9), Some(5));
+        assert_eq!(universe.distance(0, 1), None);
+        assert_eq!(universe.distance(1, 10), None);
+        assert_eq!(universe.nearest(9, 2), Some(vec![(7, 5), (8, 5)]));
+    }
+
+    #[test]
+    fn test_distance_sum_overflow() {
+        let mut universe = Universe::parse(EXAMPLE);
+        universe.set_expansion(usize::MAX);
+        assert_eq!(universe.distance_sum(), None);
+    }
+}
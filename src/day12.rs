@@ -1,10 +1,11 @@
 // let's try brute force first
 
-fn brute_force_solve(line: &str, segments: &Vec<u32>) -> u32 {
+#[allow(dead_code)]
+fn brute_force_solve(_line: &str, _segments: &[u32]) -> u32 {
     panic!("not implemented!")
 }
 
-fn parse(line: &str) -> (&str, Vec<u32>) {
+fn parse(_line: &str) -> (&str, Vec<u32>) {
     panic!("not implemented!")
 }
 
@@ -13,6 +14,6 @@ pub(crate) fn c1(input: String) -> String {
     "".to_string()
 }
 
-pub(crate) fn c2(input: String) -> String {
+pub(crate) fn c2(_input: String) -> String {
     "sum".to_string()
 }
\ No newline at end of file
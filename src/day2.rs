@@ -1,66 +1,317 @@
 use std::cmp::max;
+use std::collections::HashMap;
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Color(String);
 
+// how many cubes of each colour are in the bag
+type Bag = HashMap<Color, u32>;
 
-pub(crate) fn c1(input: String) -> String {
-    let mut sum = 0;
-    for line in input.lines() {
-        let parts = line.split(":").collect::<Vec<&str>>();
-        let id: u32 = parts[0].split(" ").collect::<Vec<&str>>()[1].parse().expect("This is not a number!");
-        let games = parts[1].split(";").collect::<Vec<&str>>();
-        // check all games for possibility (12 red, 13 green, 14 blue)
-        let mut possible = true;
-        for game in games {
-            let game = game.trim();
-            let draws = game.split(",").collect::<Vec<&str>>();
-            for draw in draws {
-                let draw = draw.trim();
-                let color = draw.split(" ").collect::<Vec<&str>>()[1];
-                let number = draw.split(" ").collect::<Vec<&str>>()[0].parse::<u32>().expect("This is not a number!");
-                if (color == "red" && number > 12) || (color == "green" && number > 13) || (color == "blue" && number > 14) {
-                    possible = false;
-                }
+#[derive(Debug, Clone, PartialEq)]
+struct Game {
+    id: u32,
+    rounds: Vec<HashMap<Color, u32>>,
+}
+
+impl Game {
+    // parses a line like "Game 1: 3 blue, 4 red; 1 red, 2 green"
+    fn parse(line: &str) -> Game {
+        let (game, rounds) = line.split_once(":").expect("This is not a game!");
+        let id = game
+            .trim()
+            .strip_prefix("Game ")
+            .expect("This is not a game!")
+            .parse()
+            .expect("This is not a number!");
+        let rounds = rounds
+            .split(";")
+            .map(|round| round
+                .split(",")
+                .map(|draw| {
+                    let (number, color) = draw.trim().split_once(" ").expect("This is not a draw!");
+                    (Color(color.to_string()), number.parse::<u32>().expect("This is not a number!"))
+                })
+                .fold(HashMap::new(), |mut round, (color, number)| {
+                    *round.entry(color).or_insert(0) += number;
+                    round
+                })
+            )
+            .collect();
+        Game { id, rounds }
+    }
+
+    // the fewest cubes of every colour that make all rounds possible
+    fn minimum_bag(&self) -> Bag {
+        let mut bag = Bag::new();
+        for round in &self.rounds {
+            for (color, &number) in round {
+                let entry = bag.entry(color.clone()).or_insert(0);
+                *entry = max(*entry, number);
             }
         }
-        if possible {
-            sum += id;
+        bag
+    }
+
+    // the index of the first round that draws more cubes of a colour than the bag holds
+    fn violating_round(&self, bag: &Bag) -> Option<usize> {
+        self.rounds.iter().position(|round| round
+            .iter()
+            .any(|(color, &number)| number > bag.get(color).copied().unwrap_or(0))
+        )
+    }
+
+    fn is_feasible(&self, bag: &Bag) -> bool {
+        self.violating_round(bag).is_none()
+    }
+}
+
+fn parse_games(input: &str) -> Vec<Game> {
+    input.lines().map(Game::parse).collect()
+}
+
+// ln(n!) for all n up to max, so binomial coefficients can be taken in log space
+fn ln_factorials(max: u32) -> Vec<f64> {
+    let mut table = vec![0.0; max as usize + 1];
+    for n in 1..table.len() {
+        table[n] = table[n - 1] + (n as f64).ln();
+    }
+    table
+}
+
+fn ln_binomial(table: &[f64], n: u32, k: u32) -> f64 {
+    table[n as usize] - table[k as usize] - table[(n - k) as usize]
+}
+
+// every round draws a handful of cubes without replacement, so the round follows
+// the multivariate hypergeometric distribution: prod(C(K_c, k_c)) / C(N, n)
+fn ln_round_probability(table: &[f64], bag: &Bag, round: &HashMap<Color, u32>) -> f64 {
+    let total = bag.values().sum::<u32>();
+    let drawn = round.values().sum::<u32>();
+    if drawn > total {
+        return f64::NEG_INFINITY;
+    }
+    let mut ln_p = -ln_binomial(table, total, drawn);
+    for (color, &number) in round {
+        match bag.get(color) {
+            Some(&available) if available >= number => ln_p += ln_binomial(table, available, number),
+            _ => return f64::NEG_INFINITY,
         }
     }
-    sum.to_string()
+    ln_p
 }
 
-pub(crate) fn c2(input: String) -> String {
-    let mut sum = 0;
-    for line in input.lines() {
-        let parts = line.split(":").collect::<Vec<&str>>();
-        let _id: u32 = parts[0].split(" ").collect::<Vec<&str>>()[1].parse().expect("This is not a number!");
-        let games = parts[1].split(";").collect::<Vec<&str>>();
-        // check all games for lowest possible score
-        let mut red: u32 = 0;
-        let mut blue: u32 = 0;
-        let mut green: u32 = 0;
-        for game in games {
-            let game = game.trim();
-            let draws = game.split(",").collect::<Vec<&str>>();
-            for draw in draws {
-                let draw = draw.trim();
-                let color = draw.split(" ").collect::<Vec<&str>>()[1];
-                let number = draw.split(" ").collect::<Vec<&str>>()[0].parse::<u32>().expect("This is not a number!");
-                match color {
-                    "red" => {
-                        red = max(red, number);
-                    },
-                    "green" => {
-                        green = max(green, number);
-                    },
-                    "blue" => {
-                        blue = max(blue, number);
-                    },
-                    _ => panic!("This is not a color!"),
+fn ln_likelihood(table: &[f64], games: &[Game], bag: &Bag) -> f64 {
+    games.iter().flat_map(|game| &game.rounds).map(|round| ln_round_probability(table, bag, round)).sum()
+}
+
+// the probability of every round of every game, if it was drawn from the given bag
+fn round_probabilities(games: &[Game], bag: &Bag) -> Vec<(u32, Vec<f64>)> {
+    let table = ln_factorials(bag.values().sum());
+    games
+        .iter()
+        .map(|game| (game.id, game.rounds.iter().map(|round| ln_round_probability(&table, bag, round).exp()).collect()))
+        .collect()
+}
+
+// hill climbing from the smallest bag that allows all games, adding, removing or swapping
+// cubes as long as the likelihood of all rounds improves, the step grows while it keeps improving
+// data drawn like with replacement pushes the bag towards infinity, so the total is capped
+fn most_likely_bag(games: &[Game], max_cubes: u32) -> Bag {
+    let minimum = games.iter().fold(Bag::new(), |mut bag, game| {
+        for (color, number) in game.minimum_bag() {
+            let entry = bag.entry(color).or_insert(0);
+            *entry = max(*entry, number);
+        }
+        bag
+    });
+    // the search works on plain counts per colour, the maps are too slow for it
+    let mut colors: Vec<Color> = minimum.keys().cloned().collect();
+    colors.sort();
+    let rounds: Vec<Vec<u32>> = games
+        .iter()
+        .flat_map(|game| &game.rounds)
+        .map(|round| colors.iter().map(|color| round.get(color).copied().unwrap_or(0)).collect())
+        .collect();
+    let minimum: Vec<u32> = colors.iter().map(|color| minimum[color]).collect();
+    // the minimum bag is always evaluated, even if it holds more than max_cubes
+    let table = ln_factorials(max(max_cubes, minimum.iter().sum()));
+    let ln_likelihood = |bag: &[u32]| -> f64 {
+        let total = bag.iter().sum::<u32>();
+        rounds
+            .iter()
+            .map(|round| {
+                let drawn = round.iter().sum::<u32>();
+                bag.iter().zip(round).map(|(&available, &number)| ln_binomial(&table, available, number)).sum::<f64>()
+                    - ln_binomial(&table, total, drawn)
+            })
+            .sum()
+    };
+    let mut bag = minimum.clone();
+    let mut best = ln_likelihood(&bag);
+    let mut step = 1;
+    loop {
+        // growing every colour at once follows the ridge of bags with a similar ratio
+        let mut candidates = vec![bag.iter().map(|number| number + step).collect::<Vec<u32>>()];
+        for add in (0..colors.len()).map(Some).chain([None]) {
+            for remove in (0..colors.len()).map(Some).chain([None]) {
+                if add == remove {
+                    continue;
                 }
+                let mut candidate = bag.clone();
+                if let Some(i) = add {
+                    candidate[i] += step;
+                }
+                if let Some(i) = remove {
+                    if candidate[i] < minimum[i] + step {
+                        continue;
+                    }
+                    candidate[i] -= step;
+                }
+                candidates.push(candidate);
             }
         }
-        sum += red * green * blue;
+        let improvement = candidates
+            .into_iter()
+            .filter(|candidate| candidate.iter().sum::<u32>() <= max_cubes)
+            .map(|candidate| (ln_likelihood(&candidate), candidate))
+            .filter(|(ln_l, _)| *ln_l > best)
+            .max_by(|(l1, _), (l2, _)| l1.total_cmp(l2));
+        match improvement {
+            Some((ln_l, candidate)) => {
+                best = ln_l;
+                bag = candidate;
+                step *= 2;
+            }
+            None if step > 1 => step /= 2,
+            None => return colors.into_iter().zip(bag).collect(),
+        }
+    }
+}
+
+fn format_bag(bag: &Bag) -> String {
+    let mut cubes: Vec<(&Color, &u32)> = bag.iter().collect();
+    cubes.sort();
+    cubes.iter().map(|(color, number)| format!("{} {}", number, color.0)).collect::<Vec<String>>().join(", ")
+}
+
+// the most likely bag behind all games, and how likely every round is under the candidate bag,
+// which defaults to the most likely one
+pub(crate) fn analyze(input: String, candidate: Option<Vec<(String, u32)>>) -> String {
+    const MAX_CUBES: u32 = 1000;
+    let games = parse_games(&input);
+    let likely = most_likely_bag(&games, MAX_CUBES);
+    let table = ln_factorials(likely.values().sum());
+    let mut output = format!(
+        "Most likely bag: {} (log-likelihood {:.3})\n",
+        format_bag(&likely),
+        ln_likelihood(&table, &games, &likely)
+    );
+    if likely.values().sum::<u32>() + likely.len() as u32 > MAX_CUBES {
+        output.push_str(&format!(
+            "The search stopped at the cap of {} cubes, the rounds look like they were drawn with replacement\n",
+            MAX_CUBES
+        ));
     }
+    let bag = match candidate {
+        Some(cubes) => cubes.into_iter().map(|(color, number)| (Color(color), number)).collect(),
+        None => likely,
+    };
+    output.push_str(&format!("Round probabilities for the bag {}:\n", format_bag(&bag)));
+    for (id, probabilities) in round_probabilities(&games, &bag) {
+        let probabilities = probabilities.iter().map(|p| format!("{:.4}", p)).collect::<Vec<String>>().join(" ");
+        output.push_str(&format!("Game {}: {}\n", id, probabilities));
+    }
+    output
+}
+
+fn feasible_id_sum(games: &[Game], bag: &Bag) -> u32 {
+    games.iter().filter(|game| game.is_feasible(bag)).map(|game| game.id).sum()
+}
+
+pub(crate) fn c1(input: String) -> String {
+    let bag: Bag = [("red", 12), ("green", 13), ("blue", 14)]
+        .into_iter()
+        .map(|(color, number)| (Color(color.to_string()), number))
+        .collect();
+    feasible_id_sum(&parse_games(&input), &bag).to_string()
+}
+
+pub(crate) fn c2(input: String) -> String {
+    // the power of a game is the product of its minimum bag, a colour it never draws counts as 0
+    let sum: u32 = parse_games(&input)
+        .iter()
+        .map(|game| {
+            let bag = game.minimum_bag();
+            ["red", "green", "blue"]
+                .iter()
+                .map(|color| bag.get(&Color(color.to_string())).copied().unwrap_or(0))
+                .product::<u32>()
+        })
+        .sum();
     sum.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(c1(EXAMPLE.to_string()), "8");
+        assert_eq!(c2(EXAMPLE.to_string()), "2286");
+        assert_eq!(c2("Game 1: 3 red, 4 blue; 1 red".to_string()), "0");
+    }
+
+    #[test]
+    fn test_queries() {
+        let games = parse_games(EXAMPLE);
+        let bag: Bag = [(Color("red".to_string()), 12), (Color("green".to_string()), 13)].into_iter().collect();
+        // no blue cubes at all, so every game fails in its first round with blue
+        assert_eq!(games[0].violating_round(&bag), Some(0));
+        assert_eq!(games[2].violating_round(&bag), Some(0));
+        let bag = games[2].minimum_bag();
+        assert_eq!(bag[&Color("red".to_string())], 20);
+        assert!(games[2].is_feasible(&bag));
+        // game 4 draws 6 red in its second round, but game 1 only needs 4
+        assert_eq!(games[3].violating_round(&games[0].minimum_bag()), Some(1));
+    }
+
+    #[test]
+    fn test_any_colour() {
+        let game = Game::parse("Game 7: 2 purple, 1 red; 5 purple");
+        assert_eq!(game.id, 7);
+        assert_eq!(game.minimum_bag()[&Color("purple".to_string())], 5);
+    }
+
+    fn bag(cubes: &[(&str, u32)]) -> Bag {
+        cubes.iter().map(|&(color, number)| (Color(color.to_string()), number)).collect()
+    }
+
+    #[test]
+    fn test_round_probabilities() {
+        let games = parse_games("Game 1: 1 red; 2 red, 1 blue; 1 green");
+        let probabilities = &round_probabilities(&games, &bag(&[("red", 2), ("blue", 1)]))[0].1;
+        assert!((probabilities[0] - 2.0 / 3.0).abs() < 1e-9);
+        assert!((probabilities[1] - 1.0).abs() < 1e-9);
+        assert_eq!(probabilities[2], 0.0);
+    }
+
+    #[test]
+    fn test_most_likely_bag() {
+        // drawing everything every time only fits the smallest bag
+        let games = parse_games("Game 1: 3 red, 2 blue; 3 red, 2 blue\nGame 2: 2 blue, 3 red");
+        assert_eq!(most_likely_bag(&games, 100), bag(&[("red", 3), ("blue", 2)]));
+        // single draws only see the ratio, the smallest bag with that ratio wins
+        let games = parse_games("Game 1: 1 red; 1 red\nGame 2: 1 blue");
+        assert_eq!(most_likely_bag(&games, 100), bag(&[("red", 2), ("blue", 1)]));
+        // nothing fits under the cap, so the minimum bag stays
+        let games = parse_games("Game 1: 8 red, 5 blue");
+        assert_eq!(most_likely_bag(&games, 10), bag(&[("red", 8), ("blue", 5)]));
+    }
+}
@@ -1,4 +1,3 @@
-use std::cmp::{max, min};
 use std::collections::HashMap;
 use regex::Regex;
 
@@ -12,100 +11,248 @@ struct Hit {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Position(usize, usize);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Symbol {
+    symbol: char,
+    position: Position,
+}
+
 impl Hit {
-    fn is_part_number(&self, array: &Vec<Vec<char>>) -> bool {
-        let mut symbols = "".to_string();
-        // get all symbols in the area around the number
-        for i in if self.line > 0 {self.line-1} else {self.line}
-            ..=if self.line+1 < array.len() {self.line+1} else {self.line} {
-            symbols += &*array
-                [max(0, i)]
-                [(if self.columns.0 > 0 {self.columns.0-1} else {self.columns.0})..min(array[self.line].len()-1, self.columns.1 + 1)].iter().collect::<String>();
-        }
-        // remove all digits from symbols
-        symbols = symbols.replace(|c: char| c.is_digit(10), "");
-        // remove all dots from symbols
-        symbols = symbols.replace(".", "");
-        // check if any symbols remain
-        symbols.len() > 0
+    // all positions around the number, the ones outside of the schematic simply never match
+    fn border(&self) -> impl Iterator<Item=Position> + '_ {
+        (self.line.saturating_sub(1)..=self.line + 1).flat_map(move |line| {
+            (self.columns.0.saturating_sub(1)..=self.columns.1).map(move |column| Position(line, column))
+        })
     }
+}
 
-    fn find_gear(&self, array: &Vec<Vec<char>>) -> Vec<Position> {
-        // get all gear symbols in the area around the number
-        let mut gears = Vec::new();
-        // compute column indices:
-        let columns = (
-            if self.columns.0 > 0 {self.columns.0-1} else {self.columns.0},
-            if self.columns.1+1 < array[self.line].len() {self.columns.1 + 1} else {self.columns.1},
-        );
-        // check the line above if it exists
-        let lines = (
-            if self.line > 0 {self.line-1} else {self.line},
-            if self.line+1 < array.len() {self.line+1} else {self.line},
-        );
-        for line in lines.0..=lines.1 {
-            for column in columns.0..columns.1 {
-                if array[line][column] == '*' {
-                    gears.push(Position(line, column));
-                }
-            }
-        }
-        gears
-    }
+// a group of numbers and symbols that touch each other, directly or through other members
+#[derive(Debug, Clone, PartialEq)]
+struct Cluster {
+    numbers: Vec<usize>,
+    symbols: Vec<usize>,
+    // top left and bottom right corner, both inclusive
+    bounds: (Position, Position),
+    sum: u32,
 }
 
-pub(crate) fn c1(input: String) -> String {
-    let array: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let mut sum = 0;
-    for (index, line) in input.lines().enumerate() {
-        // check for numbers with regex
+// numbers and symbols are indexed once, together with which of them touch
+struct Schematic {
+    numbers: Vec<Hit>,
+    symbols: Vec<Symbol>,
+    number_symbols: Vec<Vec<usize>>,
+    symbol_numbers: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    fn parse(input: &str) -> Schematic {
         let re = Regex::new(r"\d+").unwrap();
-        let numbers: Vec<Hit> = re.find_iter(line)
-            .map(|x| Hit{
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let mut symbol_positions = HashMap::new();
+        for (index, line) in input.lines().enumerate() {
+            numbers.extend(re.find_iter(line).map(|x| Hit {
                 number: x.as_str().parse().expect("This is not a number!"),
                 line: index,
                 columns: (x.start(), x.end()),
-            }).collect();
-        // check if any of the numbers is a part number
-        for number in numbers {
-            if number.is_part_number(&array) {
-                sum += number.number;
+            }));
+            for (column, symbol) in line.char_indices().filter(|&(_, c)| c != '.' && !c.is_ascii_digit()) {
+                symbol_positions.insert(Position(index, column), symbols.len());
+                symbols.push(Symbol { symbol, position: Position(index, column) });
+            }
+        }
+        let mut number_symbols = vec![Vec::new(); numbers.len()];
+        let mut symbol_numbers = vec![Vec::new(); symbols.len()];
+        for (n, number) in numbers.iter().enumerate() {
+            for s in number.border().filter_map(|position| symbol_positions.get(&position)) {
+                number_symbols[n].push(*s);
+                symbol_numbers[*s].push(n);
             }
         }
+        Schematic { numbers, symbols, number_symbols, symbol_numbers }
+    }
+
+    // connected components over numbers and symbols, where every cell touches its eight neighbours
+    // the nodes are numbers first and symbols after them, joined with union find
+    fn clusters(&self) -> Vec<Cluster> {
+        let mut cells = HashMap::new();
+        for (n, number) in self.numbers.iter().enumerate() {
+            for column in number.columns.0..number.columns.1 {
+                cells.insert(Position(number.line, column), n);
+            }
+        }
+        for (s, symbol) in self.symbols.iter().enumerate() {
+            cells.insert(symbol.position, self.numbers.len() + s);
+        }
+        let mut parents: Vec<usize> = (0..self.numbers.len() + self.symbols.len()).collect();
+        for (&Position(line, column), &node) in &cells {
+            for neighbour in (line.saturating_sub(1)..=line + 1)
+                .flat_map(|l| (column.saturating_sub(1)..=column + 1).map(move |c| Position(l, c)))
+                .filter_map(|position| cells.get(&position)) {
+                let (a, b) = (root(&mut parents, node), root(&mut parents, *neighbour));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+        // collect the members in order of their smallest node
+        let mut clusters: Vec<Cluster> = Vec::new();
+        let mut cluster_of_root = HashMap::new();
+        for node in 0..parents.len() {
+            let (position, end) = if node < self.numbers.len() {
+                let number = &self.numbers[node];
+                (Position(number.line, number.columns.0), Position(number.line, number.columns.1 - 1))
+            } else {
+                let position = self.symbols[node - self.numbers.len()].position;
+                (position, position)
+            };
+            let r = root(&mut parents, node);
+            let index = *cluster_of_root.entry(r).or_insert_with(|| {
+                clusters.push(Cluster { numbers: Vec::new(), symbols: Vec::new(), bounds: (position, end), sum: 0 });
+                clusters.len() - 1
+            });
+            let cluster = &mut clusters[index];
+            if node < self.numbers.len() {
+                cluster.numbers.push(node);
+                cluster.sum += self.numbers[node].number;
+            } else {
+                cluster.symbols.push(node - self.numbers.len());
+            }
+            cluster.bounds = (
+                Position(cluster.bounds.0.0.min(position.0), cluster.bounds.0.1.min(position.1)),
+                Position(cluster.bounds.1.0.max(end.0), cluster.bounds.1.1.max(end.1)),
+            );
+        }
+        clusters
+    }
+
+    fn symbols_touching(&self, number: usize) -> impl Iterator<Item=&Symbol> {
+        self.number_symbols[number].iter().map(|&s| &self.symbols[s])
+    }
+
+    fn numbers_touching(&self, symbol: usize) -> impl Iterator<Item=&Hit> {
+        self.symbol_numbers[symbol].iter().map(|&n| &self.numbers[n])
+    }
 
+    // all '*' symbols that touch exactly the given amount of numbers
+    fn gears(&self, neighbours: usize) -> impl Iterator<Item=(&Symbol, Vec<&Hit>)> {
+        (0..self.symbols.len())
+            .filter(move |&s| self.symbols[s].symbol == '*' && self.symbol_numbers[s].len() == neighbours)
+            .map(|s| (&self.symbols[s], self.numbers_touching(s).collect()))
     }
+}
+
+// union find lookup, halving the path on the way up
+fn root(parents: &mut [usize], node: usize) -> usize {
+    let mut node = node;
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+// every cluster with its members, bounding box and sum, one per line
+pub(crate) fn analyze(input: String) -> String {
+    let schematic = Schematic::parse(&input);
+    let mut output = String::new();
+    for (i, cluster) in schematic.clusters().iter().enumerate() {
+        let numbers = cluster.numbers
+            .iter()
+            .map(|&n| schematic.numbers[n].number.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        let symbols = cluster.symbols
+            .iter()
+            .map(|&s| format!("{} at {},{}", schematic.symbols[s].symbol, schematic.symbols[s].position.0, schematic.symbols[s].position.1))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let (top_left, bottom_right) = cluster.bounds;
+        output.push_str(&format!(
+            "Cluster {}: numbers [{}], symbols [{}], lines {}-{}, columns {}-{}, sum {}\n",
+            i + 1, numbers, symbols, top_left.0, bottom_right.0, top_left.1, bottom_right.1, cluster.sum
+        ));
+    }
+    output
+}
+
+pub(crate) fn c1(input: String) -> String {
+    let schematic = Schematic::parse(&input);
+    let sum: u32 = (0..schematic.numbers.len())
+        .filter(|&n| schematic.symbols_touching(n).next().is_some())
+        .map(|n| schematic.numbers[n].number)
+        .sum();
     sum.to_string()
 }
 
 pub(crate) fn c2(input: String) -> String {
-    let array: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let mut sum = 0;
-    let mut gears: HashMap<Position, Vec<Hit>> = HashMap::new();
-    for (index, line) in input.lines().enumerate() {
-        // check for numbers with regex
-        let re = Regex::new(r"\d+").unwrap();
-        let numbers: Vec<Hit> = re.find_iter(line)
-            .map(|x| Hit{
-                number: x.as_str().parse().expect("This is not a number!"),
-                line: index,
-                columns: (x.start(), x.end()),
-            }).collect();
-        // get all gear numbers
-        for number in numbers {
-            let gear_hits = number.find_gear(&array);
-            for gear in gear_hits {
-                if !gears.contains_key(&gear) {
-                    gears.insert(gear, Vec::new());
-                }
-                gears.get_mut(&gear).unwrap().push(number);
-            }
-        }
+    let schematic = Schematic::parse(&input);
+    let sum: u32 = schematic
+        .gears(2)
+        .map(|(_, hits)| hits[0].number * hits[1].number)
+        .sum();
+    sum.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(c1(EXAMPLE.to_string()), "4361");
+        assert_eq!(c2(EXAMPLE.to_string()), "467835");
     }
-    println!("{:?}", gears);
-    for (_gear, hits) in gears {
-        if hits.len() == 2 {
-            sum += hits[0].number * hits[1].number;
-        }
+
+    #[test]
+    fn test_adjacency() {
+        let schematic = Schematic::parse(EXAMPLE);
+        // 114 and 58 touch nothing, 467 touches the star below its end
+        assert_eq!(schematic.symbols_touching(1).count(), 0);
+        assert_eq!(
+            schematic.symbols_touching(0).collect::<Vec<&Symbol>>(),
+            vec![&Symbol { symbol: '*', position: Position(1, 3) }]
+        );
+        let numbers: Vec<u32> = schematic.numbers_touching(0).map(|hit| hit.number).collect();
+        assert_eq!(numbers, vec![467, 35]);
+        assert_eq!(schematic.gears(1).count(), 1);
+        assert_eq!(schematic.gears(1).next().unwrap().1[0].number, 617);
+    }
+
+    #[test]
+    fn test_edges() {
+        let schematic = Schematic::parse("1*\n.2");
+        assert_eq!(schematic.gears(2).count(), 1);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let schematic = Schematic::parse(EXAMPLE);
+        let clusters = schematic.clusters();
+        // 467 and 35 share a star, 114 stands alone
+        assert_eq!(clusters[0], Cluster {
+            numbers: vec![0, 2],
+            symbols: vec![0],
+            bounds: (Position(0, 0), Position(2, 3)),
+            sum: 502,
+        });
+        assert_eq!(clusters[1].numbers, vec![1]);
+        assert!(clusters[1].symbols.is_empty());
+        let members: usize = clusters.iter().map(|c| c.numbers.len() + c.symbols.len()).sum();
+        assert_eq!(members, schematic.numbers.len() + schematic.symbols.len());
+        // numbers and symbols also chain diagonally through each other
+        let clusters = Schematic::parse("1...\n.*..\n..#3\n5...").clusters();
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].sum, 4);
+        assert_eq!(clusters[0].bounds, (Position(0, 0), Position(2, 3)));
     }
-    sum.to_string()
 }
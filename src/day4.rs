@@ -1,59 +1,155 @@
+use std::collections::HashSet;
 
-fn get_line_numbers(line: &str) -> (u32, Vec<u32>, Vec<u32>) {
-    // split line into card number and list of numbers
-    let mut contents = line.split(":");
-    let index = contents.next().unwrap().split(" ").last().unwrap().parse::<u32>().unwrap();
-    let mut numbers = contents.next().unwrap().split("|");
-    let winners = numbers.next().expect("missing delimiter").split(" ").filter_map(
-        |x| if x.len() > 0 {Some(x.parse::<u32>().unwrap())} else {None}
-    ).collect();
-    let chosen = numbers.next().expect("missing delimiter").split(" ").filter_map(
-        |x| if x.len() > 0 {Some(x.parse::<u32>().unwrap())} else {None}
-    ).collect();
-    (index, winners, chosen)
-}
-
-fn get_winner_amount(winners: &Vec<u32>, chosen: &Vec<u32>) -> u32 {
-    let mut winner_sum = 0;
-    for c in chosen {
-        if winners.contains(&c) {
-            winner_sum += 1;
+#[derive(Debug, Clone, PartialEq)]
+struct Card {
+    id: u32,
+    winners: HashSet<u32>,
+    chosen: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scoring {
+    // the first match is worth one point, every further match doubles it
+    Doubling,
+    // every match wins a copy of one of the following cards, the score is the amount of cards
+    Cascade,
+}
+
+fn parse_numbers(numbers: &str) -> Result<Vec<u32>, String> {
+    numbers
+        .split_whitespace()
+        .map(|x| x.parse::<u32>().map_err(|_| format!("{:?} is not a number", x)))
+        .collect()
+}
+
+impl Card {
+    // parses a line like "Card 1: 41 48 83 | 83 86 6"
+    fn parse(line: &str) -> Result<Card, String> {
+        let (card, numbers) = line.split_once(":").ok_or(format!("{:?} is not a card", line))?;
+        let id = card
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse::<u32>().ok())
+            .ok_or(format!("{:?} is not a card number", card))?;
+        let (winners, chosen) = numbers.split_once("|").ok_or(format!("Card {} is missing the delimiter", id))?;
+        let (winners, chosen) = (parse_numbers(winners)?, parse_numbers(chosen)?);
+        for numbers in [&winners, &chosen] {
+            let mut seen = HashSet::new();
+            if let Some(duplicate) = numbers.iter().find(|&&number| !seen.insert(number)) {
+                return Err(format!("Card {} lists {} twice", id, duplicate));
+            }
         }
+        Ok(Card { id, winners: winners.into_iter().collect(), chosen })
+    }
+
+    fn matches(&self) -> u32 {
+        self.chosen.iter().filter(|number| self.winners.contains(number)).count() as u32
     }
-    winner_sum
 }
 
-pub(crate) fn c1(input: String) -> String {
-    let mut total_sum = 0;
-    for line in input.lines() {
-        let (_idx, winners, chosen) = get_line_numbers(line);
-        let winner_sum = get_winner_amount(&winners, &chosen);
-        if winner_sum > 0 {
-            total_sum += 2_u32.pow(winner_sum-1);
+// the copy cascade relies on the cards being numbered 1, 2, 3, ... in order
+fn parse_cards(input: &str) -> Result<Vec<Card>, String> {
+    let cards = input.lines().map(Card::parse).collect::<Result<Vec<Card>, String>>()?;
+    for (i, card) in cards.iter().enumerate() {
+        if card.id != i as u32 + 1 {
+            return Err(format!("Card {} is in position {}, the cards have to be numbered from 1", card.id, i + 1));
         }
     }
-   total_sum.to_string()
+    Ok(cards)
+}
+
+// a single forward pass: every card hands its copies on to the cards it wins,
+// wins running past the last card are dropped
+fn copy_counts(wins: &[u32]) -> Vec<u64> {
+    let mut copies = vec![1; wins.len()];
+    for (i, &won) in wins.iter().enumerate() {
+        let (done, rest) = copies.split_at_mut(i + 1);
+        for copy in rest.iter_mut().take(won as usize) {
+            *copy += done[i];
+        }
+    }
+    copies
+}
+
+impl Scoring {
+    fn score(&self, cards: &[Card]) -> u64 {
+        match self {
+            Scoring::Doubling => cards
+                .iter()
+                .map(Card::matches)
+                .filter(|&matches| matches > 0)
+                .map(|matches| 2_u64.pow(matches - 1))
+                .sum(),
+            Scoring::Cascade => copy_counts(&cards.iter().map(Card::matches).collect::<Vec<u32>>()).iter().sum(),
+        }
+    }
+}
+
+// the copies of every card by card number, and the total amount of cards
+fn copy_table(cards: &[Card]) -> (Vec<(u32, u64)>, u64) {
+    let copies = copy_counts(&cards.iter().map(Card::matches).collect::<Vec<u32>>());
+    let total = copies.iter().sum();
+    (cards.iter().map(|card| card.id).zip(copies).collect(), total)
+}
+
+// the copy table, one card per line
+pub(crate) fn analyze(input: String) -> String {
+    let cards = parse_cards(&input).unwrap_or_else(|error| panic!("{}", error));
+    let (table, total) = copy_table(&cards);
+    let mut output = String::new();
+    for (id, copies) in table {
+        output.push_str(&format!("Card {}: {}\n", id, copies));
+    }
+    output.push_str(&format!("Total: {}\n", total));
+    output
+}
+
+pub(crate) fn c1(input: String) -> String {
+    let cards = parse_cards(&input).unwrap_or_else(|error| panic!("{}", error));
+    Scoring::Doubling.score(&cards).to_string()
 }
 
 pub(crate) fn c2(input: String) -> String {
-    // make a vector of all the lines as a reference to get the original lines
-    let lines: Vec<(u32, u32)> = input
-        .lines()
-        .map(|l| get_line_numbers(l))
-        .map(|(i, w, c)| (i, get_winner_amount(&w, &c)))
-        .collect();
-    // make a vector of all lines as working copy
-    let mut working_lines = lines.clone();
-    // iterate over all lines
-    let mut sum = 0;
-    while let Some(line) = working_lines.pop() {
-        // increase sum by 1
-        sum += 1;
-        // get line index
-        let index = line.0;
-        // append new lines by winner amount
-        working_lines.append(lines[index as usize..(index+line.1) as usize].to_vec().as_mut());
-    }
-    sum.to_string()
+    let cards = parse_cards(&input).unwrap_or_else(|error| panic!("{}", error));
+    Scoring::Cascade.score(&cards).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(c1(EXAMPLE.to_string()), "13");
+        assert_eq!(c2(EXAMPLE.to_string()), "30");
+    }
+
+    #[test]
+    fn test_copy_table() {
+        let (table, total) = copy_table(&parse_cards(EXAMPLE).unwrap());
+        assert_eq!(table, vec![(1, 1), (2, 2), (3, 4), (4, 8), (5, 14), (6, 1)]);
+        assert_eq!(total, 30);
+    }
+
+    #[test]
+    fn test_wins_past_the_end() {
+        assert_eq!(copy_counts(&[3, 5]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(Card::parse("Card 3: 1 2 | 2 3").unwrap().matches(), 1);
+        assert_eq!(Card::parse("Card 3: 1 2 1 | 2 3"), Err("Card 3 lists 1 twice".to_string()));
+        assert_eq!(Card::parse("Card 3: 1 2 | 2 2"), Err("Card 3 lists 2 twice".to_string()));
+        assert!(Card::parse("Card 3: 1 2 2 3").is_err());
+        assert!(Card::parse("Card x: 1 | 2").is_err());
+        assert!(parse_cards("Card 1: 1 | 2\nCard 3: 1 | 2").is_err());
+        assert!(parse_cards("Card 2: 1 | 2").is_err());
+    }
 }
@@ -1,7 +1,8 @@
 use std::cmp::min;
+use crate::run::Context;
 
 #[derive(Debug, Clone)]
-struct Almanac {
+pub(crate) struct Almanac {
     seed_to_soil: Vec<(usize, usize, isize)>,
     soil_to_fertilizer: Vec<(usize, usize, isize)>,
     fertilizer_to_water: Vec<(usize, usize, isize)>,
@@ -25,6 +26,42 @@ impl Almanac {
         }
     }
 
+    // the seeds and the almanac with all translations
+    pub(crate) fn parse(input: &str) -> (Vec<usize>, Almanac) {
+        let mut almanac = Almanac::new();
+        let mut seeds: Vec<usize> = Vec::new();
+        let mut category = "";
+        for line in input.lines() {
+            // check if line starts with number, letter or is empty
+            // line is empty -> skip it
+            if line.is_empty() {
+                continue;
+            }
+            // line start with a letter -> change category
+            if line.chars().next().unwrap().is_alphabetic() {
+                // if line starts with "seeds:", add initial seeds
+                if line.starts_with("seeds:") {
+                    let seed_list = line.split(" ").skip(1);
+                    for seed in seed_list {
+                        seeds.push(seed.parse::<usize>().unwrap());
+                    }
+                }
+                category = line.split(" ").next().unwrap();
+                continue;
+            }
+            // line starts with a number -> add translation
+            let mut translation = line.split(" ");
+            almanac.add_translation(
+                translation.next().unwrap().parse::<usize>().unwrap(),
+                translation.next().unwrap().parse::<usize>().unwrap(),
+                translation.next().unwrap().parse::<usize>().unwrap(),
+                category
+            );
+
+        }
+        (seeds, almanac)
+    }
+
     fn add_translation(&mut self, output_start: usize, input_start: usize, length: usize, name: &str) {
         let translation = (input_start, input_start+length, output_start as isize - input_start as isize);
 
@@ -40,7 +77,7 @@ impl Almanac {
        }
     }
 
-    fn translate(&self, input: usize, translation: &Vec<(usize, usize, isize)>) -> usize {
+    fn translate(&self, input: usize, translation: &[(usize, usize, isize)]) -> usize {
         let translation = translation
             .iter()
             .find(|x| x.0 <= input && input < x.1);
@@ -59,109 +96,71 @@ impl Almanac {
         let light = self.translate(water, &self.water_to_light);
         let temperature = self.translate(light, &self.light_to_temperature);
         let humidity = self.translate(temperature, &self.temperature_to_humidity);
-        let location = self.translate(humidity, &self.humidity_to_location);
-        location
+        self.translate(humidity, &self.humidity_to_location)
+    }
+
+    // every step on the way from the seed to its location
+    pub(crate) fn trace(&self, seed: usize) -> Vec<(&'static str, usize)> {
+        let steps = [
+            ("soil", &self.seed_to_soil),
+            ("fertilizer", &self.soil_to_fertilizer),
+            ("water", &self.fertilizer_to_water),
+            ("light", &self.water_to_light),
+            ("temperature", &self.light_to_temperature),
+            ("humidity", &self.temperature_to_humidity),
+            ("location", &self.humidity_to_location),
+        ];
+        let mut trace = vec![("seed", seed)];
+        for (name, translation) in steps {
+            trace.push((name, self.translate(trace.last().unwrap().1, translation)));
+        }
+        trace
     }
 }
 
 
 
 pub(crate) fn c1(input: String) -> String {
-    let mut almanac = Almanac::new();
-    let mut seeds: Vec<usize> = Vec::new();
-    let mut category = "";
-    for line in input.lines() {
-        // check if line starts with number, letter or is empty
-        // line is empty -> skip it
-        if line.len() == 0 {
-            continue;
-        }
-        // line start with a letter -> change category
-        if line.chars().next().unwrap().is_alphabetic() {
-            // if line starts with "seeds:", add initial seeds
-            if line.starts_with("seeds:") {
-                let seed_list = line.split(" ").skip(1);
-                for seed in seed_list {
-                    seeds.push(seed.parse::<usize>().unwrap());
-                }
-            }
-            category = line.split(" ").next().unwrap();
-            continue;
-        }
-        // line starts with a number -> add translation
-        let mut translation = line.split(" ");
-        almanac.add_translation(
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            category
-        );
-
-    }
+    let (seeds, almanac) = Almanac::parse(&input);
     // println!("{:?}", almanac);
     let mut lowest_location = usize::MAX;
     for seed in seeds {
         let location = almanac.get_location_from_seed(seed);
-        println!("{} -> {}", seed, location);
+        debug!("seed {} -> location {}", seed, location);
         lowest_location = min(lowest_location, location);
     }
     lowest_location.to_string()
 }
 
-pub(crate) fn c2(input: String) -> String {
-    let mut almanac = Almanac::new();
-    let mut seeds: Vec<usize> = Vec::new();
-    let mut category = "";
-    for line in input.lines() {
-        // check if line starts with number, letter or is empty
-        // line is empty -> skip it
-        if line.len() == 0 {
-            continue;
-        }
-        // line start with a letter -> change category
-        if line.chars().next().unwrap().is_alphabetic() {
-            // if line starts with "seeds:", add initial seeds
-            if line.starts_with("seeds:") {
-                let seed_list = line.split(" ").skip(1);
-                for seed in seed_list {
-                    seeds.push(seed.parse::<usize>().unwrap());
-                }
-            }
-            category = line.split(" ").next().unwrap();
-            continue;
-        }
-        // line starts with a number -> add translation
-        let mut translation = line.split(" ");
-        almanac.add_translation(
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            translation.next().unwrap().parse::<usize>().unwrap(),
-            category
-        );
-
-    }
+pub(crate) fn c2(input: String, context: &Context) -> Option<String> {
+    let (seeds, almanac) = Almanac::parse(&input);
     // println!("{:?}", almanac);
     // start 10 threads
     let mut threads = Vec::new();
     for slice in seeds.chunks(2) {
         let almanac = almanac.clone();
         let slice = slice.to_vec();
-        println!("started thread with {:?} seeds", slice[1]);
+        let context = context.clone();
+        debug!("started thread with {} seeds", slice[1]);
         threads.push(std::thread::spawn(move || {
             let mut lowest_location = usize::MAX;
             for seed in slice[0]..slice[0]+slice[1] {
+                // looking at the flag for every seed would slow the lookups down
+                if seed % 65536 == 0 && context.is_cancelled() {
+                    return None;
+                }
                 let location = almanac.get_location_from_seed(seed);
                 lowest_location = min(lowest_location, location);
             }
-            lowest_location
+            Some(lowest_location)
         }));
     }
     // wait for all threads to finish
     let mut lowest_location = usize::MAX;
     for thread in threads {
-        let location = thread.join().unwrap();
-        println!("thread finished with location {}", location);
+        let location = thread.join().unwrap()?;
+        debug!("thread finished with location {}", location);
         lowest_location = min(lowest_location, location);
     }
-    lowest_location.to_string()
+    Some(lowest_location.to_string())
 }
@@ -2,7 +2,7 @@
 fn get_data_1(line: &str) -> Vec<u32> {
    line.split(" ")
        .skip(1)
-       .filter(|w| w.len() > 0)
+       .filter(|w| !w.is_empty())
        .map(|w| w.parse::<u32>().unwrap())
        .collect::<Vec<u32>>()
 }
@@ -10,7 +10,7 @@ fn get_data_1(line: &str) -> Vec<u32> {
 fn get_data_2(line: &str) -> u128 {
    line.split(" ")
        .skip(1)
-       .filter(|w| w.len() > 0)
+       .filter(|w| !w.is_empty())
        .collect::<Vec<&str>>()
        .join("")
        .parse::<u128>()
@@ -32,7 +32,7 @@ pub(crate) fn c1(input: String) -> String {
             winning_times += 1;
          }
       }
-      println!("{}...{} -> {}", t, d, winning_times);
+      debug!("race of {}ms with record {}mm can be won {} ways", t, d, winning_times);
       sum *= winning_times;
    }
    sum.to_string()
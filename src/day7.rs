@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Debug, Hash, Copy, Clone)]
 struct Card {
     value: char,
@@ -10,7 +11,7 @@ impl Card {
         Card { value }
     }
 
-    fn to_u8(&self) -> u8 {
+    fn to_u8(self) -> u8 {
         match self.value {
             'T' => 10,
             'J' => 1,
@@ -42,6 +43,7 @@ impl Ord for Card {
     }
 }
 
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Debug, Hash, Copy, Clone)]
 enum HandValue {
     HighCard = 0,
@@ -54,7 +56,7 @@ enum HandValue {
 }
 
 impl HandValue {
-    fn to_u8(&self) -> u8 {
+    fn to_u8(self) -> u8 {
         match self {
             HandValue::HighCard => 0,
             HandValue::OnePair => 1,
@@ -96,6 +98,7 @@ fn compute_hand_value(cards: &[Card; 5]) -> HandValue {
         _ => HandValue::HighCard
     }
 }
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Debug, Hash, Copy, Clone)]
 struct Hand {
     cards: [Card; 5],
@@ -147,7 +150,7 @@ pub(crate) fn c2(input: String) -> String {
             .next()
             .unwrap()
             .chars()
-            .map(|c| Card::new(c))
+            .map(Card::new)
             .collect::<Vec<Card>>()
             .try_into()
             .unwrap()
@@ -160,11 +163,11 @@ pub(crate) fn c2(input: String) -> String {
             .unwrap()
     ))
         .collect();
-    hands.sort_by(|(hand1, _), (hand2, _)| hand1.cmp(hand2));
+    hands.sort_by_key(|(hand1, _)| *hand1);
     let mut total: u32 = 0;
     for (i, (hand, bid)) in hands.iter().enumerate() {
         total += (i as u32 + 1) * bid;
-        println!("{}: {:?} {}", i + 1, hand, bid);
+        trace!("rank {}: {:?} bid {}", i + 1, hand, bid);
     }
     total.to_string()
 }
@@ -1,16 +1,27 @@
 use std::collections::HashMap;
+use std::fmt;
+use crate::run::Context;
 
 #[derive(Debug, Hash, Copy, Clone, Eq, PartialEq)]
-struct Node([char; 3]);
+pub(crate) struct Node([char; 3]);
 
 impl Node {
+    pub(crate) fn parse(name: &str) -> Option<Node> {
+        name.chars().collect::<Vec<char>>().try_into().ok().map(Node)
+    }
 
     fn ends_in_z(&self) -> bool {
         self.0[2] == 'Z'
     }
 }
 
-struct Graph {
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.iter().collect::<String>())
+    }
+}
+
+pub(crate) struct Graph {
     nodes: HashMap<Node, (Node, Node)>,
 }
 
@@ -21,6 +32,28 @@ impl Graph {
         }
     }
 
+    // the instructions and the graph of all nodes
+    pub(crate) fn parse(input: &str) -> (Vec<char>, Graph) {
+        let mut lines = input.lines();
+        let instructions = lines.next().unwrap().chars().collect::<Vec<char>>();
+        let mut graph = Graph::new();
+        for line in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let line = line.replace("(", "")
+                .replace(")", "")
+                .replace(",", "")
+                .replace("= ", "");
+            let mut values = line.split(" ");
+            let from = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
+            let left = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
+            let right = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
+            graph.add_node(from, left, right);
+        }
+        (instructions, graph)
+    }
+
     fn add_node(&mut self, node: Node, left: Node, right: Node) {
         self.nodes.insert(node, (left, right));
     }
@@ -33,6 +66,22 @@ impl Graph {
             *right
         }
     }
+
+    // every node on the way when following the instructions once, starting with the start node
+    pub(crate) fn walk(&self, start: Node, instructions: &str) -> Result<Vec<Node>, String> {
+        let mut nodes = vec![start];
+        for instruction in instructions.chars() {
+            let current = *nodes.last().unwrap();
+            if !self.nodes.contains_key(&current) {
+                return Err(format!("There is no node {}", current));
+            }
+            if instruction != 'L' && instruction != 'R' {
+                return Err(format!("{:?} is not an instruction, only L and R are", instruction));
+            }
+            nodes.push(self.follow(current, instruction));
+        }
+        Ok(nodes)
+    }
 }
 
 struct Path<'a> {
@@ -44,7 +93,7 @@ struct Path<'a> {
 }
 
 impl Path<'_> {
-    fn new(start: Node, graph: &Graph, instructions: Vec<char>) -> Path {
+    fn new(start: Node, graph: &Graph, instructions: Vec<char>) -> Path<'_> {
         Path {
             nodes: vec![(start, 0, false)],
             graph,
@@ -90,43 +139,31 @@ impl Path<'_> {
 
 }
 
-fn get_common_target(paths: Vec<Path>) -> u128 {
+// None once the context is cancelled
+fn get_common_target(paths: Vec<Path>, context: &Context) -> Option<u128> {
     // get first path
     let first = paths.first().unwrap();
     // iterate until you find an integer that is a valid target for all paths
-    let mut n = first.target_positions.first().unwrap().clone() as u128;
+    let mut n = *first.target_positions.first().unwrap() as u128;
     loop {
         if paths.iter().all(|path| path.is_target(n as i128)) {
-            return n;
+            return Some(n);
+        }
+        if context.is_cancelled() {
+            return None;
         }
         if !first.is_target(n as i128) {
-            println!("ERROR!!!!")
+            warn!("step {} is not a target of the first path", n);
         }
         n += first.nodes.len() as u128 - first.circle_start.unwrap() as u128;
         if (n % 1000000) < 1000 {
-            println!("n: {}", n);
+            trace!("checked up to step {}", n);
         }
     }
 }
 
 pub(crate) fn c1(input: String) -> String {
-    let mut lines = input.lines();
-    let instructions = lines.next().unwrap().chars().collect::<Vec<char>>();
-    let mut graph = Graph::new();
-    for line in lines {
-        if line.len() == 0 {
-            continue;
-        }
-        let line = line.replace("(", "")
-            .replace(")", "")
-            .replace(",", "")
-            .replace("= ", "");
-        let mut values = line.split(" ");
-        let from = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        let left = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        let right = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        graph.add_node(from, left, right);
-    }
+    let (instructions, graph) = Graph::parse(&input);
     // follow the yellow brick road
     let mut current = Node("AAA".chars().collect::<Vec<char>>().try_into().unwrap());
     let goal = Node("ZZZ".chars().collect::<Vec<char>>().try_into().unwrap());
@@ -138,24 +175,8 @@ pub(crate) fn c1(input: String) -> String {
     count.to_string()
 }
 
-pub(crate) fn c2(input: String) -> String {
-    let mut lines = input.lines();
-    let instructions = lines.next().unwrap().chars().collect::<Vec<char>>();
-    let mut graph = Graph::new();
-    for line in lines {
-        if line.len() == 0 {
-            continue;
-        }
-        let line = line.replace("(", "")
-            .replace(")", "")
-            .replace(",", "")
-            .replace("= ", "");
-        let mut values = line.split(" ");
-        let from = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        let left = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        let right = Node(values.next().unwrap().chars().collect::<Vec<char>>().try_into().unwrap());
-        graph.add_node(from, left, right);
-    }
+pub(crate) fn c2(input: String, context: &Context) -> Option<String> {
+    let (instructions, graph) = Graph::parse(&input);
     // follow the yellow brick road
     // until all current nodes end in Z
     let start_nodes: Vec<Node> = graph.nodes.keys().filter_map(|node| {
@@ -167,13 +188,13 @@ pub(crate) fn c2(input: String) -> String {
     }).collect();
     // find a loop for every path
     let mut paths: Vec<Path> = Vec::new();
-    for (_, n) in start_nodes.iter().enumerate() {
+    for n in start_nodes.iter() {
         let start = *n;
         // follow the yellow brick road until we reach a circle
         let mut path = Path::new(start, &graph, instructions.clone());
         path.follow_until_circle();
         paths.push(path);
     }
-    let count = get_common_target(paths);
-    count.to_string()
+    let count = get_common_target(paths, context)?;
+    Some(count.to_string())
 }
\ No newline at end of file
@@ -4,7 +4,8 @@ struct History {
 }
 
 impl History {
-    fn new(initial: Vec<i64>) -> History {
+    // returns None if any of the differences overflows an i64
+    fn new(initial: Vec<i64>) -> Option<History> {
         let mut data = vec![initial];
         while data.last()
             .unwrap()
@@ -13,48 +14,75 @@ impl History {
             let mut next_line = Vec::new();
             for pair in data.last().unwrap().windows(2) {
                 // add the difference between the two numbers to the next line
-                next_line.push(pair[1] - pair[0]);
+                next_line.push(pair[1].checked_sub(pair[0])?);
             }
             data.push(next_line);
         }
-        History {
+        Some(History {
             data
-        }
+        })
     }
 
-    fn next(&self) -> i64 {
-        let mut acc = 0;
+    fn next(&self) -> Option<i64> {
+        let mut acc: i64 = 0;
         for set in self.data.iter().rev() {
-            acc += set.last().unwrap();
+            acc = acc.checked_add(*set.last().unwrap())?;
         }
-        acc
+        Some(acc)
     }
 
-    fn prev(&self) -> i64 {
-        let mut acc = 0;
+    fn prev(&self) -> Option<i64> {
+        let mut acc: i64 = 0;
         for set in self.data.iter().rev() {
-            acc = set.first().unwrap() - acc;
+            acc = set.first().unwrap().checked_sub(acc)?;
         }
-        acc
+        Some(acc)
     }
 }
 
+fn parse_history(line: &str) -> History {
+    let numbers = line.split(" ").map(|x| x.parse::<i64>().unwrap()).collect::<Vec<i64>>();
+    History::new(numbers).expect("Overflow while computing the differences!")
+}
+
 pub(crate) fn c1(input: String) -> String {
-    let mut sum = 0;
+    let mut sum: i64 = 0;
     for line in input.lines() {
-        let numbers = line.split(" ").map(|x| x.parse::<i64>().unwrap()).collect::<Vec<i64>>();
-        let history = History::new(numbers);
-        sum += history.next();
+        let history = parse_history(line);
+        sum = history.next()
+            .and_then(|next| sum.checked_add(next))
+            .expect("Overflow while extrapolating forwards!");
     }
     sum.to_string()
 }
 
 pub(crate) fn c2(input: String) -> String {
-    let mut sum = 0;
+    let mut sum: i64 = 0;
     for line in input.lines() {
-        let numbers = line.split(" ").map(|x| x.parse::<i64>().unwrap()).collect::<Vec<i64>>();
-        let history = History::new(numbers);
-        sum += history.prev();
+        let history = parse_history(line);
+        sum = history.prev()
+            .and_then(|prev| sum.checked_add(prev))
+            .expect("Overflow while extrapolating backwards!");
     }
     sum.to_string()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extrapolate() {
+        let history = History::new(vec![10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(history.next(), Some(68));
+        assert_eq!(history.prev(), Some(5));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(History::new(vec![i64::MIN, i64::MAX]).is_none());
+        let history = History::new(vec![i64::MAX - 2, i64::MAX - 1, i64::MAX]).unwrap();
+        assert_eq!(history.next(), None);
+        assert_eq!(history.prev(), Some(i64::MAX - 3));
+    }
+}
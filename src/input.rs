@@ -0,0 +1,111 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// where the puzzle input of a challenge comes from
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "stdin"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl Source {
+    pub(crate) fn read(&self) -> Result<String, String> {
+        match self {
+            Source::Stdin => {
+                let mut contents = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut contents)
+                    .map_err(|error| format!("Could not read stdin: {}", error))?;
+                Ok(contents)
+            }
+            Source::File(path) => std::fs::read_to_string(path)
+                .map_err(|error| format!("Could not read {}: {}", path.display(), error)),
+        }
+    }
+}
+
+// the directory with all inputs, AOC_INPUT_DIR if it is set
+pub(crate) fn input_dir() -> PathBuf {
+    std::env::var_os("AOC_INPUT_DIR").map_or(PathBuf::from("input"), PathBuf::from)
+}
+
+// both parts of a day share {day}.txt, unless a {day}-{challenge}.txt overrides it for one part
+fn resolve_in(dir: &Path, day: u8, challenge: u8) -> Result<Source, String> {
+    let candidates = [
+        dir.join(format!("{}-{}.txt", day, challenge)),
+        dir.join(format!("{}.txt", day)),
+    ];
+    candidates
+        .iter()
+        .find(|path| path.is_file())
+        .map(|path| Source::File(path.clone()))
+        .ok_or(format!(
+            "There is no input for day {}, challenge {} (looked for {} and {})",
+            day, challenge, candidates[0].display(), candidates[1].display()
+        ))
+}
+
+// an explicit path wins, "-" reads from stdin, otherwise the input directory is searched
+pub(crate) fn resolve(day: u8, challenge: u8, explicit: Option<&str>) -> Result<Source, String> {
+    match explicit {
+        Some("-") => Ok(Source::Stdin),
+        Some(path) => Ok(Source::File(PathBuf::from(path))),
+        None => resolve_in(&input_dir(), day, challenge),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // a fresh directory for the files of one test, removed again even if the test fails
+    pub(crate) struct TempDir(PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!("aoc-{}-test-{}", name, std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl std::ops::Deref for TempDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_resolve_in() {
+        let dir = TempDir::new("input");
+        std::fs::write(dir.join("3.txt"), "shared").unwrap();
+        std::fs::write(dir.join("3-2.txt"), "override").unwrap();
+        assert_eq!(resolve_in(&dir, 3, 1), Ok(Source::File(dir.join("3.txt"))));
+        assert_eq!(resolve_in(&dir, 3, 2), Ok(Source::File(dir.join("3-2.txt"))));
+        assert!(resolve_in(&dir, 4, 1).is_err());
+    }
+
+    #[test]
+    fn test_resolve_explicit() {
+        assert_eq!(resolve(1, 1, Some("-")), Ok(Source::Stdin));
+        assert_eq!(resolve(1, 1, Some("other.txt")), Ok(Source::File(PathBuf::from("other.txt"))));
+    }
+}
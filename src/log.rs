@@ -0,0 +1,97 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// diagnostics go to stderr, so stdout only carries the answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Level {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+    Trace = 4,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+pub(crate) fn set_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub(crate) fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+pub(crate) fn log(level: Level, message: fmt::Arguments) {
+    if enabled(level) {
+        eprintln!("[{}] {}", level.name(), message);
+    }
+}
+
+// removes -v/-vv/-q/-qq from the arguments, every v shows one level more and every q one less than info
+pub(crate) fn take_verbosity(args: &mut Vec<String>) -> Level {
+    let mut level = Level::Info as i32;
+    args.retain(|arg| {
+        let flags = arg.strip_prefix('-').filter(|flags| !flags.is_empty());
+        match flags {
+            Some(flags) if flags.chars().all(|c| c == 'v') => level += flags.len() as i32,
+            Some(flags) if flags.chars().all(|c| c == 'q') => level -= flags.len() as i32,
+            _ => return true,
+        }
+        false
+    });
+    Level::ALL[level.clamp(Level::Error as i32, Level::Trace as i32) as usize]
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Error, format_args!($($arg)*)) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Trace, format_args!($($arg)*)) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_take_verbosity() {
+        let mut arguments = args(&["-v", "5-1", "--input", "-"]);
+        assert_eq!(take_verbosity(&mut arguments), Level::Debug);
+        assert_eq!(arguments, args(&["5-1", "--input", "-"]));
+        assert_eq!(take_verbosity(&mut args(&["-vvvvv"])), Level::Trace);
+        assert_eq!(take_verbosity(&mut args(&["-q", "-v", "-q"])), Level::Warn);
+        assert_eq!(take_verbosity(&mut args(&["-qqq"])), Level::Error);
+        assert_eq!(take_verbosity(&mut args(&[])), Level::Info);
+    }
+}
@@ -1,3 +1,8 @@
+// first, so every other module can use its macros
+#[macro_use]
+mod log;
+mod aoc;
+mod bench;
 mod day1;
 mod day2;
 mod day3;
@@ -10,98 +15,287 @@ mod day9;
 mod day10;
 mod day11;
 mod day12;
+mod input;
+mod repl;
+mod run;
+mod scaffold;
+mod submit;
+mod watch;
 
 fn main() {
-    println!("Welcome to the Advent Solver!");
-    println!("Please enter the day you would like to solve (format: [daynumber]-[challengenumber]):");
-    // Get the day-challenge input from the user
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input).expect("Failed to read line");
-    let input = input.trim();
-    let mut input = input.split("-");
-    let day = input.next().unwrap().parse::<u8>().unwrap();
-    let challenge = input.next().unwrap().parse::<u8>().unwrap();
-    // read the corresponding file
-    let filename = format!("input/{}-{}.txt", day, challenge);
-    let contents = std::fs::read_to_string(filename).expect("This file does not exist!");
-    // solve the challenge
-    match (day, challenge) {
-        (1, 1) => {
-            println!("The solution to day 1, challenge 1 is: {}", day1::c1(contents));
-        }
-        (1, 2) => {
-            println!("The solution to day 1, challenge 2 is: {}", day1::c2(contents));
-        }
-        (2, 1) => {
-            println!("The solution to day 2, challenge 1 is: {}", day2::c1(contents));
-        }
-        (2, 2) => {
-            println!("The solution to day 2, challenge 2 is: {}", day2::c2(contents));
-        }
-        (3, 1) => {
-            println!("The solution to day 3, challenge 1 is: {}", day3::c1(contents));
-        }
-        (3, 2) => {
-            println!("The solution to day 3, challenge 2 is: {}", day3::c2(contents));
-        }
-        (4, 1) => {
-            println!("The solution to day 4, challenge 1 is: {}", day4::c1(contents));
-        }
-        (4, 2) => {
-            println!("The solution to day 4, challenge 2 is: {}", day4::c2(contents));
-        }
-        (5, 1) => {
-            println!("The solution to day 5, challenge 1 is: {}", day5::c1(contents));
-        }
-        (5, 2) => {
-            println!("The solution to day 5, challenge 2 is: {}", day5::c2(contents));
-        }
-        (6, 1) => {
-            println!("The solution to day 6, challenge 1 is: {}", day6::c1(contents));
-        }
-        (6, 2) => {
-            println!("The solution to day 6, challenge 2 is: {}", day6::c2(contents));
-        }
-        (7, 1) => {
-            println!("The solution to day 7, challenge 1 is: {}", day7::c1(contents));
-        }
-        (7, 2) => {
-            println!("The solution to day 7, challenge 2 is: {}", day7::c2(contents));
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    log::set_level(log::take_verbosity(&mut args));
+    let input = take_option(&mut args, "--input");
+    let input = input.as_deref();
+    let format = take_option(&mut args, "--format").map(|format| {
+        run::OutputFormat::parse(&format).expect("The format has to be text or json!")
+    });
+    // the options are taken for every command, so tell when the command has no use for them
+    let (takes_input, takes_format) = match args.first().map(String::as_str) {
+        Some("render" | "analyze" | "submit") => (true, false),
+        Some("all" | "bench") => (false, true),
+        Some("fetch" | "report" | "new" | "watch") | None => (false, false),
+        Some(_) => (true, true),
+    };
+    let command = args.first().map_or("the repl", String::as_str);
+    if input.is_some() && !takes_input {
+        warn!("{} does not use --input, it is ignored", command);
+    }
+    if format.is_some() && !takes_format {
+        warn!("{} does not use --format, it is ignored", command);
+    }
+    let format = format.unwrap_or(run::OutputFormat::Text);
+    match args.first().map(String::as_str) {
+        Some("render") => render(args.get(1), input),
+        Some("explain") => explain(&args[1..], input, format),
+        Some("analyze") => analyze(&args[1..], input),
+        Some("fetch") => fetch(args.get(1)),
+        Some("all") => all(&mut args, format, false),
+        Some("bench") => all(&mut args, format, true),
+        Some("report") => report(&mut args),
+        Some("new") => new(args.get(1)),
+        Some("watch") => watch(&mut args),
+        Some("submit") => submit(&args[1..], input),
+        Some(puzzle) => {
+            let (day, challenge) = parse_puzzle(puzzle);
+            solve(day, challenge, input, format);
         }
-        (8, 1) => {
-            println!("The solution to day 8, challenge 1 is: {}", day8::c1(contents));
+        None => repl::repl(),
+    }
+}
+
+// removes "--name value" from the arguments and returns the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.remove(index);
+    if index < args.len() {
+        Some(args.remove(index))
+    } else {
+        panic!("{} needs a value!", name);
+    }
+}
+
+// format: [daynumber]-[challengenumber]
+fn parse_puzzle(puzzle: &str) -> (u8, u8) {
+    let mut puzzle = puzzle.split("-");
+    let day = puzzle.next().unwrap().parse::<u8>().expect("This is not a day!");
+    let challenge = puzzle.next().expect("The challenge is missing!").parse::<u8>().expect("This is not a challenge!");
+    (day, challenge)
+}
+
+fn solver(day: u8, challenge: u8) -> Option<run::Solver> {
+    use run::Solver::{Cancellable, Plain};
+    match (day, challenge) {
+        (1, 1) => Some(Plain(day1::c1)),
+        (1, 2) => Some(Plain(day1::c2)),
+        (2, 1) => Some(Plain(day2::c1)),
+        (2, 2) => Some(Plain(day2::c2)),
+        (3, 1) => Some(Plain(day3::c1)),
+        (3, 2) => Some(Plain(day3::c2)),
+        (4, 1) => Some(Plain(day4::c1)),
+        (4, 2) => Some(Plain(day4::c2)),
+        (5, 1) => Some(Plain(day5::c1)),
+        (5, 2) => Some(Cancellable(day5::c2)),
+        (6, 1) => Some(Plain(day6::c1)),
+        (6, 2) => Some(Plain(day6::c2)),
+        (7, 1) => Some(Plain(day7::c1)),
+        (7, 2) => Some(Plain(day7::c2)),
+        (8, 1) => Some(Plain(day8::c1)),
+        (8, 2) => Some(Cancellable(day8::c2)),
+        (9, 1) => Some(Plain(day9::c1)),
+        (9, 2) => Some(Plain(day9::c2)),
+        (10, 1) => Some(Plain(day10::c1)),
+        (10, 2) => Some(Plain(day10::c2)),
+        (11, 1) => Some(Plain(day11::c1)),
+        (11, 2) => Some(Plain(day11::c2)),
+        (12, 1) => Some(Plain(day12::c1)),
+        (12, 2) => Some(Plain(day12::c2)),
+        (_, _) => None,
+    }
+}
+
+// finds the input file (or stdin) of a challenge and tells which one it is
+fn read_input(day: u8, challenge: u8, explicit: Option<&str>) -> String {
+    let source = input::resolve(day, challenge, explicit).unwrap_or_else(|error| panic!("{}", error));
+    info!("Reading input from {}", source);
+    source.read().unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn solve(day: u8, challenge: u8, input: Option<&str>, format: run::OutputFormat) {
+    let record = run::run(day, challenge, input, None);
+    match (format, &record.error) {
+        (run::OutputFormat::Json, _) => println!("{}", record.to_json()),
+        (run::OutputFormat::Text, None) => println!("{}", record.to_text()),
+        (run::OutputFormat::Text, Some(error)) => error!("{}", error),
+    }
+    if record.error.is_some() {
+        std::process::exit(1);
+    }
+}
+
+// --timeout in seconds, 10 if it is not given
+fn take_timeout(args: &mut Vec<String>) -> std::time::Duration {
+    take_option(args, "--timeout").map_or(run::DEFAULT_TIMEOUT, |timeout| {
+        std::time::Duration::from_secs_f64(timeout.parse::<f64>().expect("This is not a number!"))
+    })
+}
+
+// solve a day (or only one challenge of it) again whenever its input or example file changes
+fn watch(args: &mut Vec<String>) {
+    let timeout = take_timeout(args);
+    let puzzle = args.get(1).expect("usage: watch [daynumber] or watch [daynumber]-[challengenumber]");
+    let (day, parts) = match puzzle.split_once("-") {
+        Some(_) => {
+            let (day, challenge) = parse_puzzle(puzzle);
+            (day, vec![challenge])
         }
-        (8, 2) => {
-            println!("The solution to day 8, challenge 2 is: {}", day8::c2(contents));
+        None => (puzzle.parse::<u8>().expect("This is not a day!"), vec![1, 2]),
+    };
+    watch::watch(day, &parts, Some(timeout));
+}
+
+// solve every challenge in parallel, anything still busy after --timeout seconds (default 10) is reported as TIMEOUT
+// bench runs one solver at a time unless --jobs says otherwise, and keeps the timings for report
+fn all(args: &mut Vec<String>, format: run::OutputFormat, bench: bool) {
+    let timeout = take_timeout(args);
+    let jobs = take_option(args, "--jobs").map_or_else(
+        || if bench { 1 } else { std::thread::available_parallelism().map_or(1, |jobs| jobs.get()) },
+        |jobs| jobs.parse::<usize>().expect("This is not a number!"),
+    );
+    let records = run::run_all(jobs, Some(timeout));
+    for record in &records {
+        match format {
+            run::OutputFormat::Text => println!("{}", record.to_text()),
+            run::OutputFormat::Json => println!("{}", record.to_json()),
         }
-        (9, 1) => {
-            println!("The solution to day 9, challenge 1 is: {}", day9::c1(contents));
+    }
+    if bench {
+        match bench::record(&records) {
+            Ok(message) => info!("{}", message),
+            Err(error) => error!("{}", error),
         }
-        (9, 2) => {
-            println!("The solution to day 9, challenge 2 is: {}", day9::c2(contents));
+    }
+}
+
+// compare the timings of the last bench runs, slowdowns beyond --threshold percent (default 10) are regressions
+fn report(args: &mut Vec<String>) {
+    let threshold = take_option(args, "--threshold").map_or(10.0, |threshold| threshold.parse::<f64>().expect("This is not a number!"));
+    print!("{}", bench::report(threshold));
+}
+
+// download the input of a day, unless it is already there
+fn fetch(day: Option<&String>) {
+    let day = day.and_then(|day| day.parse::<u8>().ok()).expect("usage: fetch [daynumber]");
+    match aoc::fetch(day) {
+        Ok((path, true)) => info!("Downloaded the input of day {} to {}", day, path.display()),
+        Ok((path, false)) => info!("The input of day {} is already at {}, not downloading it again", day, path.display()),
+        Err(error) => error!("Could not fetch the input of day {}: {}", day, error),
+    }
+}
+
+// create the module, registration and input files of a new day, fetch fills in the empty input later
+fn new(day: Option<&String>) {
+    let day = day.and_then(|day| day.parse::<u8>().ok()).expect("usage: new [daynumber]");
+    match scaffold::scaffold(day) {
+        Ok(created) => {
+            info!("Created day {}, rebuild to solve it:", day);
+            for path in created {
+                info!("  {}", path.display());
+            }
         }
-        (10, 1) => {
-            println!("The solution to day 10, challenge 1 is: {}", day10::c1(contents));
+        Err(error) => error!("Could not create day {}: {}", day, error),
+    }
+}
+
+// solve a challenge and send the answer, the local history keeps known wrong answers from being sent again
+fn submit(args: &[String], input: Option<&str>) {
+    let numbers: Vec<u8> = args.iter().filter_map(|arg| arg.parse().ok()).collect();
+    let (day, challenge) = match numbers[..] {
+        [day, challenge] if args.len() == 2 => (day, challenge),
+        _ => {
+            error!("usage: submit [daynumber] [challengenumber]");
+            return;
         }
-        (10, 2) => {
-            println!("The solution to day 10, challenge 2 is: {}", day10::c2(contents));
+    };
+    let Some(solver) = solver(day, challenge) else {
+        error!("This challenge has not been solved yet!");
+        return;
+    };
+    let Some(answer) = solver.solve(read_input(day, challenge, input), &run::Context::new()) else {
+        return;
+    };
+    println!("The solution to day {}, challenge {} is: {}", day, challenge, answer);
+    match submit::submit(day, challenge, &answer) {
+        Ok(verdict) => println!("{}", verdict.message()),
+        Err(error) => error!("Not submitting {}: {}", answer, error),
+    }
+}
+
+// draw the day 10 pipe maze, either coloured to the terminal or into a .txt or .svg file
+fn render(output: Option<&String>, input: Option<&str>) {
+    let contents = read_input(10, 2, input);
+    match output {
+        None => print!("{}", day10::render(contents, day10::RenderFormat::Ansi)),
+        Some(path) => {
+            let format = if path.ends_with(".svg") {
+                day10::RenderFormat::Svg
+            } else {
+                day10::RenderFormat::Text
+            };
+            std::fs::write(path, day10::render(contents, format)).expect("Could not write the file!");
+            info!("Rendered day 10 to {}", path);
         }
-        (11, 1) => {
-            println!("The solution to day 11, challenge 1 is: {}", day11::c1(contents));
+    }
+}
+
+// show how the calibration values of day 1 come about, as a table or with --format json as json
+fn explain(args: &[String], input: Option<&str>, format: run::OutputFormat) {
+    let challenge = match args.first().map(String::as_str) {
+        Some("1-1") => 1,
+        Some("1-2") => 2,
+        _ => {
+            error!("Explain mode is only available for day 1 (usage: explain 1-[challengenumber] [--format json])");
+            return;
         }
-        (11, 2) => {
-            println!("The solution to day 11, challenge 2 is: {}", day11::c2(contents));
+    };
+    let format = match format {
+        run::OutputFormat::Text => day1::ExplainFormat::Table,
+        run::OutputFormat::Json => day1::ExplainFormat::Json,
+    };
+    let contents = read_input(1, challenge, input);
+    print!("{}", day1::explain(contents, challenge, format));
+}
+
+// extra analysis of a day: the likelihood of the day 2 games, optionally against a candidate bag
+// like red=12 green=13 blue=14, the connected clusters of the day 3 schematic or the day 4 card copies
+fn analyze(args: &[String], input: Option<&str>) {
+    match args.first().map(String::as_str) {
+        Some("2") => {
+            let candidate: Vec<(String, u32)> = args[1..]
+                .iter()
+                .map(|arg| {
+                    let (color, count) = arg.split_once("=").expect("Cubes have to be given as color=count!");
+                    (color.to_string(), count.parse().expect("This is not a number!"))
+                })
+                .collect();
+            let contents = read_input(2, 1, input);
+            print!("{}", day2::analyze(contents, if candidate.is_empty() { None } else { Some(candidate) }));
         }
-        (12, 1) => {
-            println!("The solution to day 12, challenge 1 is: {}", day12::c1(contents));
+        Some("3") => {
+            let contents = read_input(3, 1, input);
+            print!("{}", day3::analyze(contents));
         }
-        (12, 2) => {
-            println!("The solution to day 12, challenge 2 is: {}", day12::c2(contents));
+        Some("4") => {
+            let contents = read_input(4, 2, input);
+            print!("{}", day4::analyze(contents));
         }
-        (_, _) => {
-            println!("This challenge has not been solved yet!");
+        Some("10") => {
+            let method = args.get(1).map_or(day10::InsideMethod::Shoelace, |method| {
+                day10::InsideMethod::parse(method).expect("The method has to be side, shoelace or squeeze!")
+            });
+            let contents = read_input(10, 2, input);
+            print!("{}", day10::analyze(contents, method));
         }
+        _ => error!("Analysis is only available for day 2, 3, 4 and 10 (usage: analyze 2 [color=count ...], analyze 3, analyze 4 or analyze 10 [side|shoelace|squeeze])"),
     }
 }
-
-
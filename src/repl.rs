@@ -0,0 +1,278 @@
+use std::io::BufRead;
+use std::panic::catch_unwind;
+use std::str::FromStr;
+use std::time::Duration;
+use crate::{day10, day11, day5, day8, run};
+
+const HELP: &str = "\
+[daynumber]-[challengenumber]   solve a challenge
+timeout [seconds]               give up on a challenge after this long, 10 unless set
+load [daynumber] [path]         parse the input of day 5, 8, 10 or 11 again, from a file if given
+translate [seed]                day 5: every step from the seed to its location
+follow [node] [instructions]    day 8: the nodes on the way, like follow AAA LRL
+tile [x] [y]                    day 10: the tile in column x and row y, counted from 0
+distance [galaxy] [galaxy]      day 11: the distance between two galaxies, numbered from 1
+nearest [galaxy] [count]        day 11: the closest galaxies
+expand [factor]                 day 11: every empty row and column becomes factor rows or columns
+metric [name]                   day 11: manhattan, chebyshev or euclidean (squared)
+history                         all commands so far, !n runs the nth one again
+quit                            leave";
+
+// the parsed inputs stay loaded between commands, every day is parsed on its first use
+struct Session {
+    almanac: Option<day5::Almanac>,
+    network: Option<(Vec<char>, day8::Graph)>,
+    maze: Option<day10::Graph>,
+    universe: Option<day11::Universe>,
+    history: Vec<String>,
+    timeout: Duration,
+}
+
+fn argument<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or(format!("The {} is missing, see help", name))?;
+    arg.parse().map_err(|_| format!("{:?} is not a valid {}", arg, name))
+}
+
+impl Session {
+    fn new() -> Session {
+        Session {
+            almanac: None,
+            network: None,
+            maze: None,
+            universe: None,
+            history: Vec::new(),
+            timeout: run::DEFAULT_TIMEOUT,
+        }
+    }
+
+    // the parsers panic on broken inputs, which must not end the session
+    fn load_contents(&mut self, day: u8, contents: String) -> Result<(), String> {
+        let failed = |_| format!("The input of day {} could not be parsed", day);
+        match day {
+            5 => self.almanac = Some(catch_unwind(|| day5::Almanac::parse(&contents).1).map_err(failed)?),
+            8 => self.network = Some(catch_unwind(|| day8::Graph::parse(&contents)).map_err(failed)?),
+            10 => self.maze = Some(catch_unwind(|| day10::Graph::parse(&contents)).map_err(failed)?),
+            11 => {
+                let mut universe = catch_unwind(|| day11::Universe::parse(&contents)).map_err(failed)?;
+                // expanded like in the first part, until expand says otherwise
+                universe.set_expansion(2);
+                self.universe = Some(universe);
+            }
+            _ => return Err(format!("Day {} cannot be explored, only days 5, 8, 10 and 11 can", day)),
+        }
+        Ok(())
+    }
+
+    fn load(&mut self, day: u8, explicit: Option<&str>) -> Result<String, String> {
+        let source = crate::input::resolve(day, 1, explicit)?;
+        self.load_contents(day, source.read()?)?;
+        Ok(format!("Loaded day {} from {}", day, source))
+    }
+
+    // loads the input of the day if it is not loaded yet
+    fn ensure(&mut self, day: u8) -> Result<(), String> {
+        let loaded = match day {
+            5 => self.almanac.is_some(),
+            8 => self.network.is_some(),
+            10 => self.maze.is_some(),
+            _ => self.universe.is_some(),
+        };
+        if !loaded {
+            info!("{}", self.load(day, None)?);
+        }
+        Ok(())
+    }
+
+    // !n stands for the nth command of the history
+    fn expand_history(&self, line: &str) -> Result<String, String> {
+        match line.strip_prefix('!') {
+            Some(number) => number
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| self.history.get(i))
+                .cloned()
+                .ok_or(format!("There is no command {} in the history", line)),
+            None => Ok(line.to_string()),
+        }
+    }
+
+    fn execute(&mut self, command: &str) -> Result<String, String> {
+        let args: Vec<&str> = command.split_whitespace().collect();
+        match args[..] {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["history"] => Ok(self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(i, command)| format!("{:>4}  {}", i + 1, command))
+                .collect::<Vec<String>>()
+                .join("\n")),
+            ["load", day] | ["load", day, _] => {
+                let day = day.parse::<u8>().map_err(|_| format!("{:?} is not a day", day))?;
+                self.load(day, args.get(2).copied())
+            }
+            ["translate", ..] => {
+                let seed = argument::<usize>(&args, 1, "seed")?;
+                self.ensure(5)?;
+                let trace = self.almanac.as_ref().unwrap().trace(seed);
+                Ok(trace.iter().map(|(name, value)| format!("{} {}", name, value)).collect::<Vec<String>>().join(" -> "))
+            }
+            ["follow", start, ..] => {
+                let start = day8::Node::parse(start).ok_or(format!("{:?} is not a node", start))?;
+                self.ensure(8)?;
+                let (instructions, graph) = self.network.as_ref().unwrap();
+                // without instructions, the ones from the input are followed once
+                let instructions = args.get(2).map_or(instructions.iter().collect::<String>(), |arg| arg.to_string());
+                let nodes = graph.walk(start, &instructions)?;
+                Ok(nodes.iter().map(|node| node.to_string()).collect::<Vec<String>>().join(" -> "))
+            }
+            ["tile", ..] => {
+                let (x, y) = (argument::<usize>(&args, 1, "column")?, argument::<usize>(&args, 2, "row")?);
+                self.ensure(10)?;
+                self.maze.as_ref().unwrap().describe_tile(x, y).ok_or(format!("There is no tile at {} {}", x, y))
+            }
+            ["distance", ..] => {
+                let (a, b) = (argument::<usize>(&args, 1, "galaxy")?, argument::<usize>(&args, 2, "galaxy")?);
+                self.ensure(11)?;
+                let universe = self.universe.as_ref().unwrap();
+                universe
+                    .distance(a, b)
+                    .map(|distance| distance.to_string())
+                    .ok_or(format!("Only galaxies 1 to {} exist, or the distance is too large", universe.len()))
+            }
+            ["nearest", ..] => {
+                let number = argument::<usize>(&args, 1, "galaxy")?;
+                let count = if args.len() > 2 { argument::<usize>(&args, 2, "count")? } else { 1 };
+                self.ensure(11)?;
+                let universe = self.universe.as_ref().unwrap();
+                let nearest = universe
+                    .nearest(number, count)
+                    .ok_or(format!("Only galaxies 1 to {} exist, or the distance is too large", universe.len()))?;
+                Ok(nearest.iter().map(|(n, distance)| format!("{} at {}", n, distance)).collect::<Vec<String>>().join(", "))
+            }
+            ["expand", ..] => {
+                let factor = argument::<usize>(&args, 1, "factor")?;
+                self.ensure(11)?;
+                self.universe.as_mut().unwrap().set_expansion(factor);
+                Ok(format!("Empty rows and columns now count {} times", factor))
+            }
+            ["metric", name] => {
+                let metric = match name {
+                    "manhattan" => day11::Metric::Manhattan,
+                    "chebyshev" => day11::Metric::Chebyshev,
+                    "euclidean" => day11::Metric::EuclideanSquared,
+                    _ => return Err(format!("{:?} is not a metric, see help", name)),
+                };
+                self.ensure(11)?;
+                self.universe.as_mut().unwrap().set_metric(metric);
+                Ok(format!("Distances are now measured with {:?}", metric))
+            }
+            ["timeout", ..] => {
+                let seconds = argument::<f64>(&args, 1, "number of seconds")?;
+                self.timeout = Duration::try_from_secs_f64(seconds).map_err(|_| format!("{} is not a valid timeout", seconds))?;
+                Ok(format!("Challenges now give up after {}s", seconds))
+            }
+            [puzzle] if puzzle.contains('-') => {
+                let (day, challenge) = puzzle.split_once('-').unwrap();
+                let (day, challenge) = day.parse::<u8>().ok().zip(challenge.parse::<u8>().ok()).ok_or("This is not a challenge!")?;
+                let record = run::run(day, challenge, None, Some(self.timeout));
+                match record.error {
+                    Some(error) => Err(error),
+                    None => Ok(record.to_text()),
+                }
+            }
+            _ => Err(format!("Unknown command {:?}, try help", command)),
+        }
+    }
+}
+
+// reads commands from stdin until quit or the end of the input, the prompt goes to stderr
+pub(crate) fn repl() {
+    eprintln!("Welcome to the Advent Solver! Enter [daynumber]-[challengenumber] to solve a challenge or help for more.");
+    let mut session = Session::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        eprint!("> ");
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let command = match session.expand_history(line.trim()) {
+            Ok(command) => command,
+            Err(error) => {
+                error!("{}", error);
+                continue;
+            }
+        };
+        if command == "quit" || command == "exit" {
+            break;
+        }
+        if command.is_empty() {
+            continue;
+        }
+        session.history.push(command.clone());
+        match session.execute(&command) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => println!("{}", output),
+            Err(error) => error!("{}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        let mut session = Session::new();
+        session.load_contents(5, include_str!("../input/5-1.txt").to_string()).unwrap();
+        session.load_contents(8, "RL\n\nAAA = (BBB, CCC)\nBBB = (DDD, EEE)\nCCC = (ZZZ, GGG)\nZZZ = (ZZZ, ZZZ)\n".to_string()).unwrap();
+        session.load_contents(10, ".....\n.S-7.\n.|.|.\n.L-J.\n.....\n".to_string()).unwrap();
+        session
+            .load_contents(11, "...#......\n.......#..\n#.........\n..........\n......#...\n.#........\n.........#\n..........\n.......#..\n#...#.....\n".to_string())
+            .unwrap();
+        session
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut session = session();
+        assert_eq!(
+            session.execute("translate 79"),
+            Ok("seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82".to_string())
+        );
+        assert_eq!(session.execute("follow AAA RL"), Ok("AAA -> CCC -> ZZZ".to_string()));
+        assert_eq!(session.execute("follow AAA"), Ok("AAA -> CCC -> ZZZ".to_string()));
+        assert!(session.execute("follow XYZ L").is_err());
+        assert_eq!(session.execute("tile 1 1"), Ok("┌ part of the loop, the start".to_string()));
+        assert_eq!(session.execute("tile 2 2"), Ok("· inside the loop".to_string()));
+        assert!(session.execute("tile 9 9").is_err());
+        assert_eq!(session.execute("distance 5 9"), Ok("9".to_string()));
+        assert_eq!(session.execute("expand 10"), Ok("Empty rows and columns now count 10 times".to_string()));
+        assert_eq!(session.execute("distance 5 9"), Ok("25".to_string()));
+        assert!(session.execute("distance 5 99").is_err());
+        assert!(session.execute("translate seventy").is_err());
+        assert!(session.execute("dance").is_err());
+        assert_eq!(session.execute("timeout 2.5"), Ok("Challenges now give up after 2.5s".to_string()));
+        assert_eq!(session.timeout, Duration::from_millis(2500));
+        assert!(session.execute("timeout -1").is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut session = session();
+        session.history.push("distance 1 7".to_string());
+        assert_eq!(session.expand_history("!1"), Ok("distance 1 7".to_string()));
+        assert!(session.expand_history("!2").is_err());
+        assert_eq!(session.expand_history("tile 3 4"), Ok("tile 3 4".to_string()));
+        assert_eq!(session.execute("history"), Ok("   1  distance 1 7".to_string()));
+    }
+
+    #[test]
+    fn test_load_broken_input() {
+        let mut session = Session::new();
+        assert!(session.load_contents(8, "RL\n\nAAAA = (B, C)\n".to_string()).is_err());
+        assert!(session.load_contents(3, String::new()).is_err());
+    }
+}
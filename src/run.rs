@@ -0,0 +1,299 @@
+use std::panic::catch_unwind;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// the error of a record whose solver ran out of time
+pub(crate) const TIMEOUT: &str = "TIMEOUT";
+// how long a solver gets unless --timeout (or timeout in the repl) says otherwise
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+// as much stack as the main thread would have had
+const SOLVER_STACK: usize = 8 * 1024 * 1024;
+
+// handed to long running solvers, which should give up once it is cancelled
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Context {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Context {
+    pub(crate) fn new() -> Context {
+        Context::default()
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Solver {
+    Plain(fn(String) -> String),
+    // returns None when it noticed the cancellation
+    Cancellable(fn(String, &Context) -> Option<String>),
+}
+
+impl Solver {
+    pub(crate) fn solve(&self, input: String, context: &Context) -> Option<String> {
+        match self {
+            Solver::Plain(solver) => Some(solver(input)),
+            Solver::Cancellable(solver) => solver(input, context),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OutputFormat {
+    // the sentence for humans
+    Text,
+    // one json object per line and solve
+    Json,
+}
+
+impl OutputFormat {
+    pub(crate) fn parse(format: &str) -> Option<OutputFormat> {
+        match format {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+// the outcome of solving one challenge, the duration only covers the solver itself
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Record {
+    pub(crate) day: u8,
+    pub(crate) part: u8,
+    pub(crate) answer: Option<String>,
+    pub(crate) duration: Duration,
+    pub(crate) input: Option<String>,
+    pub(crate) input_hash: Option<u64>,
+    pub(crate) error: Option<String>,
+}
+
+impl Record {
+    fn new(day: u8, part: u8) -> Record {
+        Record {
+            day,
+            part,
+            answer: None,
+            duration: Duration::ZERO,
+            input: None,
+            input_hash: None,
+            error: None,
+        }
+    }
+
+    fn fail(mut self, error: String) -> Record {
+        self.error = Some(error);
+        self
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let string = |value: &Option<String>| match value {
+            Some(value) => format!("\"{}\"", escape_json(value)),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"duration_ms\": {:.3}, \"input\": {}, \"input_hash\": {}, \"error\": {}}}",
+            self.day,
+            self.part,
+            string(&self.answer),
+            self.duration.as_secs_f64() * 1000.0,
+            string(&self.input),
+            string(&self.input_hash.map(|hash| format!("{:016x}", hash))),
+            string(&self.error)
+        )
+    }
+
+    pub(crate) fn to_text(&self) -> String {
+        match (&self.answer, &self.error) {
+            (Some(answer), _) => format!("The solution to day {}, challenge {} is: {}", self.day, self.part, answer),
+            (None, Some(error)) if error == TIMEOUT => {
+                format!("Day {}, challenge {}: {} after {:.3}s", self.day, self.part, TIMEOUT, self.duration.as_secs_f64())
+            }
+            (None, Some(error)) => format!("Day {}, challenge {} failed: {}", self.day, self.part, error),
+            (None, None) => format!("Day {}, challenge {} has no answer", self.day, self.part),
+        }
+    }
+}
+
+// 64 bit FNV-1a, enough to tell whether two runs saw the same input
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// the contents of a json string literal, without the quotes
+pub(crate) fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// the text of a panic, which is a &str or a String unless someone panicked with something else
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map_or("the solver panicked".to_string(), |message| message.to_string()),
+    }
+}
+
+// solves a challenge without ever panicking, everything that goes wrong ends up in the record
+// a solver that is still busy after the timeout is cancelled and left behind, only cancellable
+// solvers actually stop, the others keep a core busy until the process ends
+pub(crate) fn run(day: u8, part: u8, explicit: Option<&str>, timeout: Option<Duration>) -> Record {
+    let record = Record::new(day, part);
+    let Some(solver) = crate::solver(day, part) else {
+        return record.fail("This challenge has not been solved yet!".to_string());
+    };
+    let source = match crate::input::resolve(day, part, explicit) {
+        Ok(source) => source,
+        Err(error) => return record.fail(error),
+    };
+    info!("Reading input from {}", source);
+    let mut record = Record { input: Some(source.to_string()), ..record };
+    let contents = match source.read() {
+        Ok(contents) => contents,
+        Err(error) => return record.fail(error),
+    };
+    record.input_hash = Some(fnv1a(contents.as_bytes()));
+    let start = Instant::now();
+    let result = solve_on_thread(solver, contents, timeout);
+    record.duration = start.elapsed();
+    match result {
+        Ok(answer) => record.answer = Some(answer),
+        Err(error) => record.error = Some(error),
+    }
+    record
+}
+
+// the error is TIMEOUT if the solver did not finish in time, or the panic message
+fn solve_on_thread(solver: Solver, contents: String, timeout: Option<Duration>) -> Result<String, String> {
+    let context = Context::new();
+    let (sender, receiver) = mpsc::channel();
+    let solver_context = context.clone();
+    std::thread::Builder::new()
+        .stack_size(SOLVER_STACK)
+        .spawn(move || {
+            let result = catch_unwind(|| solver.solve(contents, &solver_context));
+            // nobody listens anymore after a timeout
+            let _ = sender.send(result);
+        })
+        .expect("Could not start the solver thread!");
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout).ok(),
+        None => receiver.recv().ok(),
+    };
+    match result {
+        Some(Ok(Some(answer))) => Ok(answer),
+        Some(Ok(None)) => Err("The solver was cancelled".to_string()),
+        Some(Err(payload)) => Err(panic_message(payload)),
+        None => {
+            context.cancel();
+            Err(TIMEOUT.to_string())
+        }
+    }
+}
+
+// solves every challenge that has a solver on a pool of threads, sorted by day and part
+pub(crate) fn run_all(jobs: usize, timeout: Option<Duration>) -> Vec<Record> {
+    let challenges = (1..=25)
+        .flat_map(|day| [(day, 1), (day, 2)])
+        .filter(|&(day, part)| crate::solver(day, part).is_some());
+    let queue = Mutex::new(challenges.collect::<Vec<(u8, u8)>>().into_iter());
+    let records = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| {
+                loop {
+                    // a separate statement, so the lock is released before solving
+                    let next = queue.lock().unwrap().next();
+                    let Some((day, part)) = next else {
+                        break;
+                    };
+                    let record = run(day, part, None, timeout);
+                    records.lock().unwrap().push(record);
+                }
+            });
+        }
+    });
+    let mut records = records.into_inner().unwrap();
+    records.sort_by_key(|record| (record.day, record.part));
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::TempDir;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = TempDir::new("run");
+        let path = dir.join("9.txt");
+        std::fs::write(&path, "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n").unwrap();
+        let record = run(9, 1, path.to_str(), None);
+        assert_eq!(record.answer.as_deref(), Some("114"));
+        assert_eq!(record.input.as_deref(), path.to_str());
+        assert_eq!(record.error, None);
+        std::fs::write(&path, "this is not a history\n").unwrap();
+        let record = run(9, 1, path.to_str(), None);
+        assert_eq!(record.answer, None);
+        assert!(record.error.is_some());
+        assert!(run(9, 1, Some("does/not/exist.txt"), None).error.unwrap().contains("Could not read"));
+        assert_eq!(run(30, 1, None, None).error.as_deref(), Some("This challenge has not been solved yet!"));
+    }
+
+    static SPIN_STOPPED: AtomicBool = AtomicBool::new(false);
+
+    fn spin(_input: String, context: &Context) -> Option<String> {
+        while !context.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        SPIN_STOPPED.store(true, Ordering::Relaxed);
+        None
+    }
+
+    #[test]
+    fn test_timeout() {
+        let result = solve_on_thread(Solver::Cancellable(spin), String::new(), Some(Duration::from_millis(20)));
+        assert_eq!(result, Err(TIMEOUT.to_string()));
+        let start = Instant::now();
+        while !SPIN_STOPPED.load(Ordering::Relaxed) {
+            assert!(start.elapsed() < Duration::from_secs(5), "the solver ignored the cancellation");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let result = solve_on_thread(Solver::Plain(|input| input.to_uppercase()), "abc".to_string(), Some(Duration::from_secs(5)));
+        assert_eq!(result, Ok("ABC".to_string()));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut record = Record::new(3, 2);
+        record.answer = Some("4\"2".to_string());
+        record.duration = Duration::from_micros(1500);
+        record.input_hash = Some(255);
+        assert_eq!(
+            record.to_json(),
+            r#"{"day": 3, "part": 2, "answer": "4\"2", "duration_ms": 1.500, "input": null, "input_hash": "00000000000000ff", "error": null}"#
+        );
+    }
+}
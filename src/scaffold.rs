@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+// DAY is replaced with the number of the day
+const TEMPLATE: &str = r#"pub(crate) fn c1(_input: String) -> String {
+    "".to_string()
+}
+
+pub(crate) fn c2(_input: String) -> String {
+    "".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/DAY-example.txt");
+
+    #[test]
+    fn test_example() {
+        // fill in the answers of the example once it is in input/DAY-example.txt
+        assert_eq!(c1(EXAMPLE.to_string()), "");
+        assert_eq!(c2(EXAMPLE.to_string()), "");
+    }
+}
+"#;
+
+fn module(day: u8) -> String {
+    TEMPLATE.replace("DAY", &day.to_string())
+}
+
+// adds "mod dayN;" after the other days and the two solver arms in front of the fallback arm
+fn register(main: &str, day: u8) -> Result<String, String> {
+    let declaration = format!("mod day{};\n", day);
+    if main.contains(&declaration) {
+        return Err(format!("day{} is already registered in main.rs", day));
+    }
+    let last_day = main
+        .match_indices("mod day")
+        .map(|(index, _)| index + main[index..].find('\n').unwrap() + 1)
+        .last()
+        .ok_or("main.rs has no day modules")?;
+    let fallback = main.find("        (_, _) => None,").ok_or("main.rs has no solver fallback arm")?;
+    let arms = format!(
+        "        ({day}, 1) => Some(Plain(day{day}::c1)),\n        ({day}, 2) => Some(Plain(day{day}::c2)),\n",
+        day = day
+    );
+    Ok(format!(
+        "{}{}{}{}{}",
+        &main[..last_day],
+        declaration,
+        &main[last_day..fallback],
+        arms,
+        &main[fallback..]
+    ))
+}
+
+// returns all files that were created or changed
+// the example goes to {root}/input regardless of AOC_INPUT_DIR, because the template includes it at compile time
+fn scaffold_in(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let (src, inputs) = (root.join("src"), root.join("input"));
+    let write = |path: &Path, contents: &str| {
+        std::fs::write(path, contents).map_err(|error| format!("Could not write {}: {}", path.display(), error))
+    };
+    let source = src.join(format!("day{}.rs", day));
+    if source.exists() {
+        return Err(format!("{} already exists", source.display()));
+    }
+    let main_path = src.join("main.rs");
+    let main = std::fs::read_to_string(&main_path)
+        .map_err(|error| format!("Could not read {}: {}", main_path.display(), error))?;
+    let main = register(&main, day)?;
+    std::fs::create_dir_all(&inputs).map_err(|error| format!("Could not create {}: {}", inputs.display(), error))?;
+    let mut created = vec![source.clone(), main_path.clone()];
+    write(&source, &module(day))?;
+    write(&main_path, &main)?;
+    // existing inputs, for example from fetch, are kept, an empty {day}.txt is fetched over later
+    for name in [format!("{}.txt", day), format!("{}-example.txt", day)] {
+        let path = inputs.join(name);
+        if !path.exists() {
+            write(&path, "")?;
+            created.push(path);
+        }
+    }
+    Ok(created)
+}
+
+pub(crate) fn scaffold(day: u8) -> Result<Vec<PathBuf>, String> {
+    scaffold_in(Path::new(""), day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::tests::mock_server;
+    use crate::aoc::{fetch_into, Config};
+    use crate::input::tests::TempDir;
+
+    #[test]
+    fn test_register() {
+        let main = include_str!("main.rs");
+        let registered = register(main, 99).unwrap();
+        assert!(registered.contains("mod day99;\nmod input;\n"));
+        assert!(registered.contains("        (99, 2) => Some(Plain(day99::c2)),\n        (_, _) => None,"));
+        assert!(register(&registered, 99).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_in() {
+        let dir = TempDir::new("scaffold");
+        let (src, inputs) = (dir.join("src"), dir.join("input"));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("main.rs"), include_str!("main.rs")).unwrap();
+        std::fs::create_dir_all(&inputs).unwrap();
+        std::fs::write(inputs.join("99.txt"), "fetched").unwrap();
+        let created = scaffold_in(&dir, 99).unwrap();
+        assert_eq!(created, vec![src.join("day99.rs"), src.join("main.rs"), inputs.join("99-example.txt")]);
+        let module = std::fs::read_to_string(src.join("day99.rs")).unwrap();
+        assert!(module.contains("input/99-example.txt"));
+        // the module only needs itself, anything else would be an unused import until the day is solved
+        assert!(module.contains("mod tests {\n    use super::*;\n\n    const EXAMPLE"));
+        assert!(!module.contains("crate::"));
+        assert_eq!(std::fs::read_to_string(inputs.join("99.txt")).unwrap(), "fetched");
+        assert!(scaffold_in(&dir, 99).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_then_fetch() {
+        let dir = TempDir::new("scaffold-fetch");
+        let (src, inputs) = (dir.join("src"), dir.join("input"));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("main.rs"), include_str!("main.rs")).unwrap();
+        scaffold_in(&dir, 99).unwrap();
+        assert_eq!(std::fs::read_to_string(inputs.join("99.txt")).unwrap(), "");
+        let (url, server) = mock_server("1 2 3\n");
+        let mut config = Config::new();
+        config.base_url = url;
+        config.session = Some("abc".to_string());
+        assert_eq!(fetch_into(&config, &inputs, 99), Ok((inputs.join("99.txt"), true)));
+        server.join().unwrap();
+        assert_eq!(std::fs::read_to_string(inputs.join("99.txt")).unwrap(), "1 2 3\n");
+    }
+}
@@ -0,0 +1,286 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use regex::Regex;
+use crate::aoc::Config;
+
+const HISTORY_FILE: &str = "submissions.txt";
+// the site makes you wait at least a minute after every wrong answer
+const WRONG_ANSWER_WAIT: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    // with the seconds left to wait, if the site told them
+    RateLimited(Option<u64>),
+    // the part is already solved or not unlocked yet
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    fn parse(html: &str) -> Verdict {
+        if html.contains("That's the right answer") {
+            Verdict::Right
+        } else if html.contains("That's not the right answer") {
+            if html.contains("too high") {
+                Verdict::TooHigh
+            } else if html.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if html.contains("You gave an answer too recently") {
+            let wait = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+            Verdict::RateLimited(wait.captures(html).map(|captures| {
+                let minutes = captures.get(1).map_or(0, |m| m.as_str().parse::<u64>().unwrap());
+                minutes * 60 + captures[2].parse::<u64>().unwrap()
+            }))
+        } else if html.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Verdict::Right => "right".to_string(),
+            Verdict::Wrong => "wrong".to_string(),
+            Verdict::TooHigh => "too_high".to_string(),
+            Verdict::TooLow => "too_low".to_string(),
+            Verdict::RateLimited(None) => "rate_limited".to_string(),
+            Verdict::RateLimited(Some(wait)) => format!("rate_limited:{}", wait),
+            Verdict::WrongLevel => "wrong_level".to_string(),
+            Verdict::Unknown => "unknown".to_string(),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Verdict> {
+        match name.split_once(':') {
+            Some(("rate_limited", wait)) => wait.parse().ok().map(|wait| Verdict::RateLimited(Some(wait))),
+            Some(_) => None,
+            None => [Verdict::Right, Verdict::Wrong, Verdict::TooHigh, Verdict::TooLow,
+                Verdict::RateLimited(None), Verdict::WrongLevel, Verdict::Unknown]
+                .into_iter()
+                .find(|verdict| verdict.name() == name),
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+
+    pub(crate) fn message(&self) -> String {
+        match self {
+            Verdict::Right => "That's the right answer!".to_string(),
+            Verdict::Wrong => "That's not the right answer.".to_string(),
+            Verdict::TooHigh => "That's not the right answer, it is too high.".to_string(),
+            Verdict::TooLow => "That's not the right answer, it is too low.".to_string(),
+            Verdict::RateLimited(Some(wait)) => format!("You gave an answer too recently, wait {}s.", wait),
+            Verdict::RateLimited(None) => "You gave an answer too recently.".to_string(),
+            Verdict::WrongLevel => "This part is already solved or not unlocked yet.".to_string(),
+            Verdict::Unknown => "The response could not be understood.".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Submission {
+    timestamp: u64,
+    year: u16,
+    day: u8,
+    part: u8,
+    verdict: Verdict,
+    answer: String,
+}
+
+impl Submission {
+    // one tab separated line: timestamp, year, day, part, verdict and answer
+    fn parse(line: &str) -> Option<Submission> {
+        let mut fields = line.splitn(6, '\t');
+        Some(Submission {
+            timestamp: fields.next()?.parse().ok()?,
+            year: fields.next()?.parse().ok()?,
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+            verdict: Verdict::from_name(fields.next()?)?,
+            answer: fields.next()?.to_string(),
+        })
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.timestamp,
+            self.year,
+            self.day,
+            self.part,
+            self.verdict.name(),
+            self.answer
+        )
+    }
+
+    // until when the site will not take another answer
+    fn blocked_until(&self) -> u64 {
+        match self.verdict {
+            Verdict::RateLimited(wait) => self.timestamp + wait.unwrap_or(WRONG_ANSWER_WAIT),
+            verdict if verdict.is_wrong() => self.timestamp + WRONG_ANSWER_WAIT,
+            _ => self.timestamp,
+        }
+    }
+}
+
+struct History {
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    // lines that cannot be read are skipped
+    fn load(path: &Path) -> History {
+        let contents = std::fs::read_to_string(path).unwrap_or_default();
+        History {
+            submissions: contents.lines().filter_map(Submission::parse).collect(),
+        }
+    }
+
+    fn append(&mut self, path: &Path, submission: Submission) -> Result<(), String> {
+        use std::io::Write;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(submission.to_line().as_bytes()))
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+
+    // why the answer must not be sent, if there is a reason
+    // earlier answers only count for the same year, the waiting time is shared by all of them
+    fn check(&self, year: u16, day: u8, part: u8, answer: &str, now: u64) -> Result<(), String> {
+        let numeric = answer.parse::<i128>().ok();
+        for submission in self.submissions.iter().filter(|s| s.year == year && s.day == day && s.part == part) {
+            let known = submission.answer.parse::<i128>().ok();
+            match (submission.verdict, numeric, known) {
+                (Verdict::Right, _, _) => {
+                    return Err(format!("Day {} part {} is already solved with {}", day, part, submission.answer));
+                }
+                (verdict, _, _) if verdict.is_wrong() && submission.answer == answer => {
+                    return Err(format!("{} was already submitted and is wrong", answer));
+                }
+                (Verdict::TooHigh, Some(answer), Some(high)) if answer >= high => {
+                    return Err(format!("{} cannot be right, {} was already too high", answer, high));
+                }
+                (Verdict::TooLow, Some(answer), Some(low)) if answer <= low => {
+                    return Err(format!("{} cannot be right, {} was already too low", answer, low));
+                }
+                _ => {}
+            }
+        }
+        if let Some(until) = self.submissions.iter().map(Submission::blocked_until).max() {
+            if until > now {
+                return Err(format!("The last answer was too recent, wait another {}s", until - now));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs())
+}
+
+fn submit_with(config: &Config, history_path: &Path, day: u8, part: u8, answer: &str, now: u64) -> Result<Verdict, String> {
+    let mut history = History::load(history_path);
+    history.check(config.year, day, part, answer, now)?;
+    let level = part.to_string();
+    let html = config.post(&format!("{}/day/{}/answer", config.year, day), &[("level", &level), ("answer", answer)])?;
+    let verdict = Verdict::parse(&html);
+    let submission = Submission { timestamp: now, year: config.year, day, part, verdict, answer: answer.to_string() };
+    history.append(history_path, submission)?;
+    Ok(verdict)
+}
+
+// sends the answer, unless the local history already shows that it is wrong or too early
+pub(crate) fn submit(day: u8, part: u8, answer: &str) -> Result<Verdict, String> {
+    submit_with(&Config::load()?, Path::new(HISTORY_FILE), day, part, answer, now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc::tests::mock_server;
+    use crate::input::tests::TempDir;
+
+    fn submission(timestamp: u64, verdict: Verdict, answer: &str) -> Submission {
+        Submission { timestamp, year: 2023, day: 1, part: 2, verdict, answer: answer.to_string() }
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(Verdict::parse("<p>That's the right answer! You are one gold star closer"), Verdict::Right);
+        assert_eq!(Verdict::parse("<p>That's not the right answer; your answer is too high."), Verdict::TooHigh);
+        assert_eq!(Verdict::parse("<p>That's not the right answer; your answer is too low."), Verdict::TooLow);
+        assert_eq!(Verdict::parse("<p>That's not the right answer. If you're stuck"), Verdict::Wrong);
+        assert_eq!(
+            Verdict::parse("<p>You gave an answer too recently. You have 1m 5s left to wait."),
+            Verdict::RateLimited(Some(65))
+        );
+        assert_eq!(Verdict::parse("<p>You don't seem to be solving the right level."), Verdict::WrongLevel);
+        assert_eq!(Verdict::parse("<html></html>"), Verdict::Unknown);
+        for verdict in [Verdict::TooLow, Verdict::RateLimited(Some(3)), Verdict::RateLimited(None)] {
+            assert_eq!(Verdict::from_name(&verdict.name()), Some(verdict));
+        }
+    }
+
+    #[test]
+    fn test_history_line() {
+        let submission = submission(1700000000, Verdict::TooHigh, "123");
+        assert_eq!(Submission::parse(submission.to_line().trim_end()), Some(submission));
+        assert_eq!(Submission::parse("garbage"), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let history = History {
+            submissions: vec![
+                submission(0, Verdict::TooHigh, "100"),
+                submission(100, Verdict::TooLow, "20"),
+                submission(200, Verdict::Wrong, "50"),
+            ],
+        };
+        assert!(history.check(2023, 1, 2, "30", 300).is_ok());
+        assert!(history.check(2023, 1, 2, "100", 300).is_err());
+        assert!(history.check(2023, 1, 2, "150", 300).is_err());
+        assert!(history.check(2023, 1, 2, "20", 300).is_err());
+        assert!(history.check(2023, 1, 2, "50", 300).is_err());
+        // a minute has to pass after the last wrong answer
+        assert!(history.check(2023, 1, 2, "30", 230).is_err());
+        // other parts and years only share the waiting time
+        assert!(history.check(2023, 1, 1, "150", 300).is_ok());
+        assert!(history.check(2022, 1, 2, "150", 300).is_ok());
+        assert!(history.check(2022, 1, 2, "150", 230).is_err());
+        let history = History { submissions: vec![submission(0, Verdict::Right, "42")] };
+        assert!(history.check(2023, 1, 2, "43", 300).is_err());
+        assert!(history.check(2022, 1, 2, "43", 300).is_ok());
+    }
+
+    #[test]
+    fn test_submit_with() {
+        let (url, server) = mock_server("<article><p>That's not the right answer; your answer is too high.</p></article>");
+        let dir = TempDir::new("submit");
+        let path = dir.join("submissions.txt");
+        let mut config = Config::new();
+        config.base_url = url;
+        config.session = Some("abc".to_string());
+        assert_eq!(submit_with(&config, &path, 1, 2, "100", 1000), Ok(Verdict::TooHigh));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2023/day/1/answer "));
+        assert!(request.ends_with("level=2&answer=100"));
+        // refused from the history alone, the mock server is gone
+        assert!(submit_with(&config, &path, 1, 2, "120", 2000).unwrap_err().contains("too high"));
+    }
+}
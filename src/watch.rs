@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::run::{self, Record};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// modification time and size of every watched file, None if it does not exist (yet)
+type Snapshot = Vec<Option<(SystemTime, u64)>>;
+
+// every file that can be the input or the example of the day, so creating an override counts as a change
+fn watched_files(dir: &Path, day: u8) -> Vec<PathBuf> {
+    ["", "-1", "-2", "-example"]
+        .iter()
+        .map(|suffix| dir.join(format!("{}{}.txt", day, suffix)))
+        .collect()
+}
+
+fn snapshot(files: &[PathBuf]) -> Snapshot {
+    files
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+fn outcome(record: &Record) -> String {
+    match (&record.answer, &record.error) {
+        (Some(answer), _) => answer.clone(),
+        (None, Some(error)) if error == run::TIMEOUT => run::TIMEOUT.to_string(),
+        (None, Some(error)) => format!("failed: {}", error),
+        (None, None) => "no answer".to_string(),
+    }
+}
+
+// one line per solve, with the previous outcome if it changed
+fn describe(label: &str, previous: Option<&String>, current: &str) -> String {
+    match previous {
+        None => format!("{}: {}", label, current),
+        Some(previous) if previous == current => format!("{}: {} (unchanged)", label, current),
+        Some(previous) => format!("{}: {} (was {})", label, current, previous),
+    }
+}
+
+// solves the parts on the input and, if there is one, on the example every time one of the files changes
+// the source itself is not watched, rebuilding and restarting stays with the caller
+pub(crate) fn watch(day: u8, parts: &[u8], timeout: Option<Duration>) {
+    let dir = crate::input::input_dir();
+    let files = watched_files(&dir, day);
+    let example = files.last().unwrap().clone();
+    let mut outcomes: HashMap<String, String> = HashMap::new();
+    let mut last: Option<Snapshot> = None;
+    info!("Watching {} for changes", files.iter().map(|path| path.display().to_string()).collect::<Vec<String>>().join(", "));
+    loop {
+        let current = snapshot(&files);
+        if last.as_ref() != Some(&current) {
+            if last.is_some() {
+                info!("Input changed, solving day {} again", day);
+            }
+            for &part in parts {
+                let mut records = vec![(format!("day {}-{} input", day, part), run::run(day, part, None, timeout))];
+                if example.is_file() {
+                    let record = run::run(day, part, example.to_str(), timeout);
+                    records.push((format!("day {}-{} example", day, part), record));
+                }
+                for (label, record) in records {
+                    let current = outcome(&record);
+                    println!("{}", describe(&label, outcomes.get(&label), &current));
+                    outcomes.insert(label, current);
+                }
+            }
+            last = Some(current);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::TempDir;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe("day 9-1 input", None, "114"), "day 9-1 input: 114");
+        assert_eq!(describe("day 9-1 input", Some(&"114".to_string()), "114"), "day 9-1 input: 114 (unchanged)");
+        assert_eq!(describe("day 9-1 input", Some(&"114".to_string()), "115"), "day 9-1 input: 115 (was 114)");
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = TempDir::new("watch");
+        let files = watched_files(&dir, 9);
+        let empty = snapshot(&files);
+        assert!(empty.iter().all(Option::is_none));
+        std::fs::write(dir.join("9-example.txt"), "0 3 6").unwrap();
+        let example = snapshot(&files);
+        assert_ne!(empty, example);
+        assert_eq!(example, snapshot(&files));
+        // the size changes even if the modification time is too coarse to notice
+        std::fs::write(dir.join("9-example.txt"), "0 3 6 9").unwrap();
+        assert_ne!(example, snapshot(&files));
+    }
+}